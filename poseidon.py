@@ -441,51 +441,81 @@ def mix_s(t, S, r, state):
     ]
     return result
 
-def poseidon_hash(a, b, initial_state=0):
-    """Computes the Poseidon hash for two inputs a and b."""
+def poseidon_hash(a, b, initial_state=0, trace=None):
+    """Computes the Poseidon hash for two inputs a and b.
+
+    When a list is passed as `trace`, the state after every step is appended to it in
+    the format of the Rust crate's `Trace`.
+    """
     t = 3  # 2 inputs + 1 state
     n_rounds_f = 8  # Full rounds
     n_rounds_p = 57  # Partial rounds
 
+    def record(round, step, state):
+        if trace is not None:
+            trace.append({"round": round, "step": step, "state": [str(x % CIRCOM_P) for x in state]})
+        return state
+
     state = [initial_state, a, b]
 
     # Initial Ark
-    state = ark(t, POSEIDON_C, 0, state)
+    state = record(0, "ark", ark(t, POSEIDON_C, 0, state))
 
     # First half of full rounds
     for r in range(n_rounds_f // 2 - 1):
-        state = [sigma(x) for x in state]
-        state = ark(t, POSEIDON_C, (r + 1) * t, state)
-        state = mix(t, POSEIDON_M, state)
+        state = record(r, "sbox", [sigma(x) for x in state])
+        state = record(r, "ark", ark(t, POSEIDON_C, (r + 1) * t, state))
+        state = record(r, "mix", mix(t, POSEIDON_M, state))
 
     # Middle round
-    state = [sigma(x) for x in state]
-    state = ark(t, POSEIDON_C, (n_rounds_f // 2) * t, state)
-    state = mix(t, POSEIDON_P, state)
+    r = n_rounds_f // 2 - 1
+    state = record(r, "sbox", [sigma(x) for x in state])
+    state = record(r, "ark", ark(t, POSEIDON_C, (n_rounds_f // 2) * t, state))
+    state = record(r, "mix", mix(t, POSEIDON_P, state))
 
     # Partial rounds
     for r in range(n_rounds_p):
-        state = mix_s(t, POSEIDON_S, r, [sigma(state[0]) + POSEIDON_C[(n_rounds_f // 2 + 1) * t + r]] + state[1:])
+        round = n_rounds_f // 2 + r
+        state = record(round, "sbox", [sigma(state[0])] + state[1:])
+        state = record(round, "ark", [state[0] + POSEIDON_C[(n_rounds_f // 2 + 1) * t + r]] + state[1:])
+        state = record(round, "mix", mix_s(t, POSEIDON_S, r, state))
 
     # Second half of full rounds
     for r in range(n_rounds_f // 2 - 1):
-        state = [sigma(x) for x in state]
-        state = ark(t, POSEIDON_C, (n_rounds_f // 2 + 1) * t + n_rounds_p + r * t, state)
-        state = mix(t, POSEIDON_M, state)
+        round = n_rounds_f // 2 + n_rounds_p + r
+        state = record(round, "sbox", [sigma(x) for x in state])
+        state = record(round, "ark", ark(t, POSEIDON_C, (n_rounds_f // 2 + 1) * t + n_rounds_p + r * t, state))
+        state = record(round, "mix", mix(t, POSEIDON_M, state))
 
     # Final round
-    state = [sigma(x) for x in state]
+    round = n_rounds_f + n_rounds_p - 1
+    state = record(round, "sbox", [sigma(x) for x in state])
 
     # Final MixLast
-    return mix_last(t, POSEIDON_M, 0, state)
+    result = mix_last(t, POSEIDON_M, 0, state)
+    record(round, "mix_last", [result])
+    return result
 
 
 if __name__ == "__main__":
     import argparse, json
     parser = argparse.ArgumentParser(description="Compute the Poseidon hash for two inputs from a JSON file.")
     parser.add_argument("json_file", type=str, help="Path to the JSON file containing the inputs.")
+    parser.add_argument("--trace", action="store_true", help="Print the per-round state trace as JSON.")
+    parser.add_argument("--kat", action="store_true", help="Treat the file as a known-answer vector file and print every output as JSON.")
     args = parser.parse_args()
 
+    if args.kat:
+        with open(args.json_file, "r") as file:
+            vectors = json.load(file)["vectors"]
+        outputs = [
+            str(poseidon_hash(*[int(x, 0) for x in v["inputs"]], initial_state=int(v.get("initial_state", "0"), 0)))
+            if v["width"] == 3 else None
+            for v in vectors
+        ]
+        print(json.dumps(outputs))
+        raise SystemExit(0)
+
     # Read and parse the JSON file
     with open(args.json_file, "r") as file:
         data = json.load(file)
@@ -497,5 +527,10 @@ if __name__ == "__main__":
     a, b = data["inputs"]
 
     # Compute the Poseidon hash
-    result = poseidon_hash(a=a, b=b)
-    print("Poseidon hash result:", result)
+    if args.trace:
+        steps = []
+        result = poseidon_hash(a=a, b=b, trace=steps)
+        print(json.dumps({"inputs": [str(a), str(b)], "output": str(result), "steps": steps}, indent=2))
+    else:
+        result = poseidon_hash(a=a, b=b)
+        print("Poseidon hash result:", result)
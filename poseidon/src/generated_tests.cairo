@@ -0,0 +1,1317 @@
+// Generated by `cargo run -- cairo-tests 32 8101821134059892590` in rust-poseidon; do not edit.
+use core::circuit::u384;
+use super::run_poseidon_grumpkin_circuit;
+
+#[test]
+fn test_edge_0() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 29709149613028773277428238436,
+        limb1: 18852914565546398353953203413,
+        limb2: 2348897666712444587,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_0');
+}
+
+#[test]
+fn test_edge_1() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 46254622486599578374201317564,
+        limb1: 63103836699945846558939419238,
+        limb2: 2932650619215892598,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_1');
+}
+
+#[test]
+fn test_edge_2() {
+    let x: u384 = u384 { limb0: 1, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 62749861268250019842633251711,
+        limb1: 32833810305268784027428951867,
+        limb2: 2934984261756153481,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_2');
+}
+
+#[test]
+fn test_edge_3() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 1, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 59580489606575408647970743902,
+        limb1: 61359594485155757257282849690,
+        limb2: 2004673807689619504,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_3');
+}
+
+#[test]
+fn test_edge_4() {
+    let x: u384 = u384 { limb0: 1, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 1, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 62961948724758107637940231809,
+        limb1: 49045972521048430327912245130,
+        limb2: 34607432937440295,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_4');
+}
+
+#[test]
+fn test_edge_5() {
+    let x: u384 = u384 { limb0: 1, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 16753924396918611063291603900,
+        limb1: 29844527954788245257460811222,
+        limb2: 905224410990783776,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_5');
+}
+
+#[test]
+fn test_edge_6() {
+    let x: u384 = u384 { limb0: 2, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 75073531492892179430533572320,
+        limb1: 6507902503660832523406335521,
+        limb2: 2791999935170843352,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_6');
+}
+
+#[test]
+fn test_edge_7() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 2, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 56006317575310159630502076856,
+        limb1: 52384155572673921840515749918,
+        limb2: 458853057636742852,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_7');
+}
+
+#[test]
+fn test_edge_8() {
+    let x: u384 = u384 { limb0: 2, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 2, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 46439763505077994289443132313,
+        limb1: 38816595693076716473927772011,
+        limb2: 748655550662988952,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_8');
+}
+
+#[test]
+fn test_edge_9() {
+    let x: u384 = u384 { limb0: 2, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 73705264871578814286955634932,
+        limb1: 31826779549499468748973710880,
+        limb2: 1266823822274305087,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_9');
+}
+
+#[test]
+fn test_edge_10() {
+    let x: u384 = u384 { limb0: 79228162514264337593543950335, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 5507434867117166213041610877,
+        limb1: 45794149788354610724456619504,
+        limb2: 3080116814891718915,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_10');
+}
+
+#[test]
+fn test_edge_11() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 79228162514264337593543950335, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 15782502231630929420877348916,
+        limb1: 1508181787273271436228480401,
+        limb2: 383223143129574031,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_11');
+}
+
+#[test]
+fn test_edge_12() {
+    let x: u384 = u384 { limb0: 79228162514264337593543950335, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 79228162514264337593543950335, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 1649542775759240017743812146,
+        limb1: 41201084741233593736381053788,
+        limb2: 1023312305450828643,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_12');
+}
+
+#[test]
+fn test_edge_13() {
+    let x: u384 = u384 { limb0: 79228162514264337593543950335, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 35617925997589838001884034619,
+        limb1: 58792605978683562069375516862,
+        limb2: 2840698558176788039,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_13');
+}
+
+#[test]
+fn test_edge_14() {
+    let x: u384 = u384 { limb0: 0, limb1: 1, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 6259633609072427561323448222,
+        limb1: 72827463077963167817424003618,
+        limb2: 1302449176550391027,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_14');
+}
+
+#[test]
+fn test_edge_15() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 1, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 24124827729134688051401262820,
+        limb1: 22211761055304037697325629205,
+        limb2: 2346212070328963176,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_15');
+}
+
+#[test]
+fn test_edge_16() {
+    let x: u384 = u384 { limb0: 0, limb1: 1, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 1, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 70954302010107642227474189032,
+        limb1: 8375475771937922662783633522,
+        limb2: 1217758002738139266,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_16');
+}
+
+#[test]
+fn test_edge_17() {
+    let x: u384 = u384 { limb0: 0, limb1: 1, limb2: 0, limb3: 0 };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 61620738590789505233579208881,
+        limb1: 54332086292610031104178748199,
+        limb2: 1770529324731888696,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_17');
+}
+
+#[test]
+fn test_edge_18() {
+    let x: u384 = u384 {
+        limb0: 79228162514264337593543950335,
+        limb1: 79228162514264337593543950335,
+        limb2: 0,
+        limb3: 0,
+    };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 56903548759814653760744860376,
+        limb1: 37301585621107902343164030445,
+        limb2: 3017929201489702191,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_18');
+}
+
+#[test]
+fn test_edge_19() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 {
+        limb0: 79228162514264337593543950335,
+        limb1: 79228162514264337593543950335,
+        limb2: 0,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 47959267068680049946104934705,
+        limb1: 35092577096988303013999153888,
+        limb2: 2351231483099689107,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_19');
+}
+
+#[test]
+fn test_edge_20() {
+    let x: u384 = u384 {
+        limb0: 79228162514264337593543950335,
+        limb1: 79228162514264337593543950335,
+        limb2: 0,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 79228162514264337593543950335,
+        limb1: 79228162514264337593543950335,
+        limb2: 0,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 7410536350140138053077293120,
+        limb1: 458917990073550362556644306,
+        limb2: 1294322042861390184,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_20');
+}
+
+#[test]
+fn test_edge_21() {
+    let x: u384 = u384 {
+        limb0: 79228162514264337593543950335,
+        limb1: 79228162514264337593543950335,
+        limb2: 0,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 63407717406810412283256626205,
+        limb1: 3290422377524583014722506154,
+        limb2: 392851404148835428,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_21');
+}
+
+#[test]
+fn test_edge_22() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 1, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 24481781045921985068482846041,
+        limb1: 14834181082293657044693630855,
+        limb2: 99416486641381390,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_22');
+}
+
+#[test]
+fn test_edge_23() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 1, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 46996842574847506049804975502,
+        limb1: 40693837050591555809306598057,
+        limb2: 2388303607618404414,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_23');
+}
+
+#[test]
+fn test_edge_24() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 1, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 1, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 75953452358237984957260348248,
+        limb1: 28440273347228056325525992558,
+        limb2: 2884950337828602411,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_24');
+}
+
+#[test]
+fn test_edge_25() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 1, limb3: 0 };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 23750615588913116715204209758,
+        limb1: 31044802025404903206913914430,
+        limb2: 1682985206262908627,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_25');
+}
+
+#[test]
+fn test_edge_26() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 2305843009213693952, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 52459169836655075060819590858,
+        limb1: 55130997438224407992722188431,
+        limb2: 3095543875789456706,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_26');
+}
+
+#[test]
+fn test_edge_27() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 2305843009213693952, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 55413511468882824203962412941,
+        limb1: 34006554621050029117310359598,
+        limb2: 2388595749316699052,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_27');
+}
+
+#[test]
+fn test_edge_28() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 2305843009213693952, limb3: 0 };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 2305843009213693952, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 60414732456209309756519949522,
+        limb1: 46240001191006384235238340430,
+        limb2: 2567574645362622853,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_28');
+}
+
+#[test]
+fn test_edge_29() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 2305843009213693952, limb3: 0 };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 12545671435953970985082475514,
+        limb1: 76162551722529774908783672596,
+        limb2: 497039647711834533,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_29');
+}
+
+#[test]
+fn test_edge_30() {
+    let x: u384 = u384 {
+        limb0: 37671869049726892487204667391,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 12905878806190388766355609698,
+        limb1: 64234085688559434054735271785,
+        limb2: 3013678912175857623,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_30');
+}
+
+#[test]
+fn test_edge_31() {
+    let x: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667391,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 51858639053741995707404500633,
+        limb1: 38074529562315768971160331131,
+        limb2: 450696479282580816,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_31');
+}
+
+#[test]
+fn test_edge_32() {
+    let x: u384 = u384 {
+        limb0: 37671869049726892487204667391,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667391,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 77959276569170706273335132149,
+        limb1: 67246697471466699537868022,
+        limb2: 2346224017592478225,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_32');
+}
+
+#[test]
+fn test_edge_33() {
+    let x: u384 = u384 {
+        limb0: 37671869049726892487204667391,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 66899529973816712903722956292,
+        limb1: 45860528861972561304975979431,
+        limb2: 3184390303404334139,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_33');
+}
+
+#[test]
+fn test_edge_34() {
+    let x: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let y: u384 = u384 { limb0: 0, limb1: 0, limb2: 0, limb3: 0 };
+    let z: u384 = u384 {
+        limb0: 65892154947607041026674496568,
+        limb1: 68433709341095495481839074587,
+        limb2: 1975196421038118323,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_34');
+}
+
+#[test]
+fn test_edge_35() {
+    let x: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 37671869049726892487204667392,
+        limb1: 57042285082623239460012419144,
+        limb2: 3486998266802970665,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 25625393208894154414469079291,
+        limb1: 17192771725559855134819439287,
+        limb2: 3200889539081242497,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'edge_35');
+}
+
+#[test]
+fn test_random_0() {
+    let x: u384 = u384 {
+        limb0: 12298057244737175733100933679,
+        limb1: 19530178003503370438281420619,
+        limb2: 344112034778781482,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 1599710153519607040864726571,
+        limb1: 36750579799316437525914584486,
+        limb2: 3269420720591762973,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 66578753410251032563379798109,
+        limb1: 57701517294359210002788209574,
+        limb2: 297368341009301057,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_0');
+}
+
+#[test]
+fn test_random_1() {
+    let x: u384 = u384 {
+        limb0: 69830007471074674965880271510,
+        limb1: 59909227547565301355027174840,
+        limb2: 2313368426489829488,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 11187747108438761555791656385,
+        limb1: 54681486632602954185188959381,
+        limb2: 1527043985214027581,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 43021516948088076311458973873,
+        limb1: 50440341426047324384709933242,
+        limb2: 768573402002827833,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_1');
+}
+
+#[test]
+fn test_random_2() {
+    let x: u384 = u384 {
+        limb0: 12288022632438356515562731683,
+        limb1: 2155659089417471084263994328,
+        limb2: 2807919686853375200,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 17242195714982062479149866613,
+        limb1: 3321176914325768843726064980,
+        limb2: 1726479124896189168,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 73797872240207683937944579633,
+        limb1: 49218110264563345146103799943,
+        limb2: 1050874764462955583,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_2');
+}
+
+#[test]
+fn test_random_3() {
+    let x: u384 = u384 {
+        limb0: 11891741563074945235726047299,
+        limb1: 17287534003020814814659706772,
+        limb2: 3331998019821463105,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 69792779611244900712464452871,
+        limb1: 58876979351752392233092978959,
+        limb2: 818664190401270423,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 46043500213138008626605467489,
+        limb1: 8988777916737969205335115645,
+        limb2: 2433185278569451366,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_3');
+}
+
+#[test]
+fn test_random_4() {
+    let x: u384 = u384 {
+        limb0: 57154660199827425438910814542,
+        limb1: 19454848763476837000109627442,
+        limb2: 218674223954071261,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 35656338871602317737619704411,
+        limb1: 52614265298781578802647546876,
+        limb2: 1389517152841608081,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 44931204126054430721890141240,
+        limb1: 24874507936802361581237199651,
+        limb2: 968343204306211429,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_4');
+}
+
+#[test]
+fn test_random_5() {
+    let x: u384 = u384 {
+        limb0: 13638650280399155589438254039,
+        limb1: 16013511610083832759115113381,
+        limb2: 2661653071833566629,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 74127202260940805466422934575,
+        limb1: 2041255128107708878676545084,
+        limb2: 1942333434707260316,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 39922523142621594826584327420,
+        limb1: 31248384386084029938959224976,
+        limb2: 737489654122633467,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_5');
+}
+
+#[test]
+fn test_random_6() {
+    let x: u384 = u384 {
+        limb0: 74214096962974182398978743158,
+        limb1: 9531554927179532952025702620,
+        limb2: 575900523542932629,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 25390101393408621145523421920,
+        limb1: 57918661345553074660750955861,
+        limb2: 2590908503823459782,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 56713940681626485604884409392,
+        limb1: 25255992930844627720804927810,
+        limb2: 3481435708579108085,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_6');
+}
+
+#[test]
+fn test_random_7() {
+    let x: u384 = u384 {
+        limb0: 78702281764080567830667810182,
+        limb1: 61696724077740923289389284254,
+        limb2: 678559655574856564,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 48848510029370603184144133579,
+        limb1: 7273799391252843031648559810,
+        limb2: 3017058411582194340,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 15567246224861353766654608113,
+        limb1: 22561182036021891776991575339,
+        limb2: 3169341518700240838,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_7');
+}
+
+#[test]
+fn test_random_8() {
+    let x: u384 = u384 {
+        limb0: 46129624955294139594659205404,
+        limb1: 68018657038457006267165269911,
+        limb2: 2073771027045294676,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 52618301398743633069429847978,
+        limb1: 68372265454722411369276245915,
+        limb2: 2732431598603863916,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 59449581850991576567014018735,
+        limb1: 15638305487940928396068391142,
+        limb2: 1962124483016054344,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_8');
+}
+
+#[test]
+fn test_random_9() {
+    let x: u384 = u384 {
+        limb0: 45301382122190423087962252024,
+        limb1: 60346387627431873364759454589,
+        limb2: 2480328548474803379,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 49272772355830771825836807787,
+        limb1: 68550012570654411521553332256,
+        limb2: 1030999685874738790,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 35101274281701349185451630279,
+        limb1: 45298802946286220954461439313,
+        limb2: 364110961066251512,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_9');
+}
+
+#[test]
+fn test_random_10() {
+    let x: u384 = u384 {
+        limb0: 42180991081847975973501480176,
+        limb1: 50670733058842089797552355777,
+        limb2: 808733774824894813,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 1462871398617814651143125209,
+        limb1: 72579224535278208621547204617,
+        limb2: 3437838223368311552,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 78669082574176183035586684469,
+        limb1: 33294438669679735833094596488,
+        limb2: 2616223771436278612,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_10');
+}
+
+#[test]
+fn test_random_11() {
+    let x: u384 = u384 {
+        limb0: 5458606554566898820431994942,
+        limb1: 12299767179666228495667273740,
+        limb2: 1685261913640707201,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 50665067550746377138186136390,
+        limb1: 65226565367733690520723360783,
+        limb2: 3448045714567248103,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 67053452706353168144342785164,
+        limb1: 75219145234751554451641600725,
+        limb2: 2856514249993234396,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_11');
+}
+
+#[test]
+fn test_random_12() {
+    let x: u384 = u384 {
+        limb0: 7878776249158716667159764454,
+        limb1: 9473631172728951709771743249,
+        limb2: 1546141816771005496,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 66086011067674875956122182963,
+        limb1: 5324787187036274185350053567,
+        limb2: 669745351486850151,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 40218454728389734658069273208,
+        limb1: 594082781956987194618058818,
+        limb2: 558100779474687716,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_12');
+}
+
+#[test]
+fn test_random_13() {
+    let x: u384 = u384 {
+        limb0: 13407924449987397452079681006,
+        limb1: 5912994594462172703721309468,
+        limb2: 2654896798159189044,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 4693625610200524452700307690,
+        limb1: 67866776654387684277394990103,
+        limb2: 2501719092060159250,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 25509380049454923460861318896,
+        limb1: 12807963895985486802251602020,
+        limb2: 2524618848756790159,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_13');
+}
+
+#[test]
+fn test_random_14() {
+    let x: u384 = u384 {
+        limb0: 8642695974144357104597319582,
+        limb1: 24566180485835046809922492112,
+        limb2: 349012572824311,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 13659079842770446425944764945,
+        limb1: 2173125196234027231016037852,
+        limb2: 2854953966523600250,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 6418510323439251041698336015,
+        limb1: 16813906966006778793576434235,
+        limb2: 2654179670778209331,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_14');
+}
+
+#[test]
+fn test_random_15() {
+    let x: u384 = u384 {
+        limb0: 34323340716991789731940154028,
+        limb1: 7278311389718106145723220786,
+        limb2: 906984761794293331,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 2831820653043101469431116178,
+        limb1: 48630240238180144530642434712,
+        limb2: 1301364132736573057,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 17078652192919581803930055085,
+        limb1: 13261320321416909904881974967,
+        limb2: 1437831151418098484,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_15');
+}
+
+#[test]
+fn test_random_16() {
+    let x: u384 = u384 {
+        limb0: 66984593336666456535146175488,
+        limb1: 22176456443516191903199297114,
+        limb2: 444604217487930835,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 31860787964208744088725807144,
+        limb1: 569618195452529967666340082,
+        limb2: 884090166509285443,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 9639423677396095272031193619,
+        limb1: 45622948180573862922102703683,
+        limb2: 3108646757434828230,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_16');
+}
+
+#[test]
+fn test_random_17() {
+    let x: u384 = u384 {
+        limb0: 1041831744619467417968193839,
+        limb1: 28538374748100533727812352314,
+        limb2: 1933276607763917349,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 23345177373555752772866849461,
+        limb1: 30237263249107795925725784285,
+        limb2: 1256729444242879481,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 6123876488201162259940023068,
+        limb1: 49599854476383517925397882412,
+        limb2: 155223773641702359,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_17');
+}
+
+#[test]
+fn test_random_18() {
+    let x: u384 = u384 {
+        limb0: 37712309097127319826832951752,
+        limb1: 33512240178536587780755011541,
+        limb2: 763099945166896927,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 7384922631398520560352241674,
+        limb1: 41341654912628033432100397998,
+        limb2: 1124580071609770218,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 23392315281148596437767862648,
+        limb1: 28012490500901065274164239821,
+        limb2: 139288110840860856,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_18');
+}
+
+#[test]
+fn test_random_19() {
+    let x: u384 = u384 {
+        limb0: 49180634669999675340225805844,
+        limb1: 21925915526632344191625088389,
+        limb2: 3422959546966310465,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 52678061918527471801367583043,
+        limb1: 70210507466209629642576983347,
+        limb2: 67221910900160392,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 52605094105731194359405981313,
+        limb1: 59573750636461019350112968250,
+        limb2: 3268766520672391825,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_19');
+}
+
+#[test]
+fn test_random_20() {
+    let x: u384 = u384 {
+        limb0: 23946687714227063014384335495,
+        limb1: 35101843961149532799933980306,
+        limb2: 1884177222006838199,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 63087086174384188012687936120,
+        limb1: 73124636010771743453642965787,
+        limb2: 183692730019402454,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 4262670294622621703901692739,
+        limb1: 21355461600671512550697994677,
+        limb2: 2024776318769076354,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_20');
+}
+
+#[test]
+fn test_random_21() {
+    let x: u384 = u384 {
+        limb0: 69179593436839870505388464016,
+        limb1: 40659660238931335675525981074,
+        limb2: 2049218890215031124,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 21939986164697464528190393394,
+        limb1: 1522557233489379950459011180,
+        limb2: 2802476054628898647,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 36136365769196705413875454055,
+        limb1: 9241017195517298645328419523,
+        limb2: 2468656233603060126,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_21');
+}
+
+#[test]
+fn test_random_22() {
+    let x: u384 = u384 {
+        limb0: 50454079820369743248856022245,
+        limb1: 48970143079283151304724781660,
+        limb2: 3414531907149758845,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 10372484591370421041785452382,
+        limb1: 54871505466272626528837101460,
+        limb2: 922226736967399639,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 40979850106876329948455175539,
+        limb1: 42638271267123738521072687751,
+        limb2: 1504691029085999650,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_22');
+}
+
+#[test]
+fn test_random_23() {
+    let x: u384 = u384 {
+        limb0: 52531879213767857436129651262,
+        limb1: 77742329395067489538485349994,
+        limb2: 3126803537714427863,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 78030081334278515100416730804,
+        limb1: 76588900131191351066461552686,
+        limb2: 2289682016584343621,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 58214884506375574256749141766,
+        limb1: 63250558613879501619867152365,
+        limb2: 1740250618075960606,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_23');
+}
+
+#[test]
+fn test_random_24() {
+    let x: u384 = u384 {
+        limb0: 19255515865509696591393650477,
+        limb1: 75122156112175922600814465984,
+        limb2: 795098201527978681,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 70127568011534435598642699988,
+        limb1: 32815157700940298370125896700,
+        limb2: 353013424497808050,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 7223091073230836948655862654,
+        limb1: 54033355745822841440406194630,
+        limb2: 1676190270213750408,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_24');
+}
+
+#[test]
+fn test_random_25() {
+    let x: u384 = u384 {
+        limb0: 15835019908792077678323876137,
+        limb1: 44079747731437915599820009296,
+        limb2: 225011954568831078,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 22519796666589677205187517979,
+        limb1: 60773471737063004641673793116,
+        limb2: 2956308847277381686,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 71544588612701195679443653725,
+        limb1: 55226917680469823325239949216,
+        limb2: 3146211722893106467,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_25');
+}
+
+#[test]
+fn test_random_26() {
+    let x: u384 = u384 {
+        limb0: 14077272927827383795245947076,
+        limb1: 26468151409470346485793139731,
+        limb2: 2739600669434829973,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 45993019799626721126613151367,
+        limb1: 64651863045532212046168395462,
+        limb2: 2860476869483734789,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 53323698813083492665904328125,
+        limb1: 10674768830974973175696515161,
+        limb2: 277354271223829617,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_26');
+}
+
+#[test]
+fn test_random_27() {
+    let x: u384 = u384 {
+        limb0: 60876964108873281703972612578,
+        limb1: 43475011608332556459399797954,
+        limb2: 2718063442776709660,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 23387174236924974117099790883,
+        limb1: 20511504889656986288659726341,
+        limb2: 472798198899918251,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 9405971666585442059964118751,
+        limb1: 60258094634780562052628563711,
+        limb2: 2910735547060965146,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_27');
+}
+
+#[test]
+fn test_random_28() {
+    let x: u384 = u384 {
+        limb0: 32773143450403156081944745787,
+        limb1: 31444903580174276683575729217,
+        limb2: 3191477935484107491,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 77150749164996146792113186088,
+        limb1: 50722864710019242149388404014,
+        limb2: 3473893158436133887,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 30122914321542075792763714324,
+        limb1: 54515303458347370585405504986,
+        limb2: 2251809195157985453,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_28');
+}
+
+#[test]
+fn test_random_29() {
+    let x: u384 = u384 {
+        limb0: 30171850098290094196794002978,
+        limb1: 76000219503239167986929053726,
+        limb2: 583196537720855116,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 22789941621815791877850631506,
+        limb1: 4638256217384671491968476585,
+        limb2: 2963116147711956264,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 23119577024912687887393043273,
+        limb1: 50942331330785570232755474561,
+        limb2: 2130972923017087089,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_29');
+}
+
+#[test]
+fn test_random_30() {
+    let x: u384 = u384 {
+        limb0: 49732779234383442975612362396,
+        limb1: 725240635292484327325987436,
+        limb2: 1120361031530213543,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 50695161085143181110396436951,
+        limb1: 55470154588965053002359569162,
+        limb2: 1145045451910086987,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 11324000470641266525746872069,
+        limb1: 77444427916271356193774562938,
+        limb2: 440289527104360433,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_30');
+}
+
+#[test]
+fn test_random_31() {
+    let x: u384 = u384 {
+        limb0: 20954209036354030837396567935,
+        limb1: 41497761650385722758358541195,
+        limb2: 2500143444371939283,
+        limb3: 0,
+    };
+    let y: u384 = u384 {
+        limb0: 46985016325788540768095720572,
+        limb1: 23529444708269372242427363521,
+        limb2: 2867355464943894214,
+        limb3: 0,
+    };
+    let z: u384 = u384 {
+        limb0: 25930927714263771170946765040,
+        limb1: 12327880961519848038593003947,
+        limb2: 1548862443765754780,
+        limb3: 0,
+    };
+    assert(run_poseidon_grumpkin_circuit(x, y) == z, 'random_31');
+}
@@ -3810,6 +3810,9 @@ pub fn run_poseidon_grumpkin_circuit(x: u384, y: u384) -> u384 {
     return z;
 }
 
+#[cfg(test)]
+mod generated_tests;
+
 #[cfg(test)]
 mod tests {
     use core::circuit::u384;
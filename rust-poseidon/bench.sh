@@ -0,0 +1,52 @@
+#!/usr/bin/env bash
+# Records Criterion baselines under target/criterion and compares later runs against them.
+#
+#   ./bench.sh save [baseline]      run the benchmarks and store them as `baseline` (default: main)
+#   ./bench.sh compare [baseline]   run them again and report the change against `baseline`
+#
+# `compare` exits with status 1 when a benchmark got slower by more than THRESHOLD (default
+# 0.05, i.e. 5%) with the whole confidence interval above it. Extra arguments after the
+# baseline name are passed to Criterion, e.g. a filter: ./bench.sh compare main merkle
+# The comparison reads Criterion's estimates with jq.
+set -euo pipefail
+cd "$(dirname "$0")"
+
+command=${1:-}
+baseline=${2:-main}
+shift $(($# < 2 ? $# : 2))
+criterion_dir="${CARGO_TARGET_DIR:-target}/criterion"
+
+case "$command" in
+save)
+    cargo bench --bench poseidon -- --save-baseline "$baseline" "$@"
+    ;;
+compare)
+    marker=$(mktemp)
+    trap 'rm -f "$marker"' EXIT
+    cargo bench --bench poseidon -- --baseline "$baseline" "$@"
+    find "$criterion_dir" -path '*/change/estimates.json' -newer "$marker" |
+        sort |
+        while read -r path; do
+            name=${path#"$criterion_dir"/}
+            jq -r --arg name "${name%/change/estimates.json}" \
+                '.mean | [$name, .point_estimate, .confidence_interval.lower_bound,
+                    .confidence_interval.upper_bound] | @tsv' "$path"
+        done |
+        awk -F '\t' -v threshold="${THRESHOLD:-0.05}" '
+            BEGIN { printf "%-48s %9s %21s\n", "benchmark", "change", "95% interval" }
+            {
+                regressed = $3 > threshold
+                regressions += regressed
+                printf "%-48s %+8.2f%% [%+7.2f%%, %+7.2f%%]%s\n", $1, 100 * $2, 100 * $3, 100 * $4,
+                    regressed ? "  REGRESSION" : ""
+            }
+            END {
+                printf "%d regression(s) above %g%%\n", regressions, 100 * threshold
+                exit regressions > 0
+            }'
+    ;;
+*)
+    sed -n '2,10p' "$0" | sed 's/^# \{0,1\}//'
+    exit 2
+    ;;
+esac
@@ -0,0 +1,233 @@
+use ark_ff::Field;
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup,
+    BenchmarkId, Criterion, Throughput,
+};
+use rust_poseidon::{
+    babybear::{self, BabyBear},
+    constants::{CircomPoseidonConstants, T},
+    field::Fr,
+    functional::{
+        poseidon_hash, poseidon_hash_batch, poseidon_hash_traced, poseidon_hash_with_constants,
+    },
+    lanes::poseidon_hash_lanes,
+    merkle::MerkleTree,
+    mix, mont,
+    packed::permute_packed,
+    permutation::Permutation,
+    poseidon2_constants::Poseidon2Constants,
+};
+
+const BATCH_SIZES: [usize; 3] = [16, 256, 4096];
+const MERKLE_LEAVES: [usize; 3] = [1 << 4, 1 << 8, 1 << 12];
+
+fn inputs(n: usize) -> Vec<[Fr; 2]> {
+    (0..n as u64)
+        .map(|i| [Fr::from(2 * i + 1), Fr::from(2 * i + 2)])
+        .collect()
+}
+
+/// One hash per width; `T` is the only width implemented so far.
+fn hash(c: &mut Criterion) {
+    let constants = CircomPoseidonConstants::default();
+
+    let mut group = c.benchmark_group("hash");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function(BenchmarkId::new("poseidon_hash", T), |b| {
+        b.iter(|| poseidon_hash(black_box(Fr::from(1)), black_box(Fr::from(2))))
+    });
+    group.bench_function(BenchmarkId::new("poseidon_hash_with_constants", T), |b| {
+        b.iter(|| {
+            poseidon_hash_with_constants(
+                &constants,
+                Fr::default(),
+                black_box(Fr::from(1)),
+                black_box(Fr::from(2)),
+            )
+        })
+    });
+    group.bench_function(BenchmarkId::new("poseidon_hash_traced", T), |b| {
+        b.iter(|| poseidon_hash_traced(black_box(Fr::from(1)), black_box(Fr::from(2))))
+    });
+    group.finish();
+}
+
+/// The width-3 permutation of circomlib's Poseidon against Poseidon2's.
+fn permutation(c: &mut Criterion) {
+    let state = [Fr::from(0), Fr::from(1), Fr::from(2)];
+
+    let mut group = c.benchmark_group("permutation");
+    group.bench_function("poseidon", |b| {
+        b.iter(|| {
+            let mut state = black_box(state);
+            CircomPoseidonConstants::shared().permute(&mut state);
+            state
+        })
+    });
+    group.bench_function("poseidon2", |b| {
+        b.iter(|| {
+            let mut state = black_box(state);
+            Poseidon2Constants::<3>::shared().permute(&mut state);
+            state
+        })
+    });
+    group.finish();
+}
+
+fn batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch");
+    for size in BATCH_SIZES {
+        let inputs = inputs(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &inputs, |b, inputs| {
+            b.iter(|| poseidon_hash_batch(black_box(inputs)))
+        });
+    }
+    group.finish();
+}
+
+/// The same 1024 hashes, run `N` at a time in lockstep.
+fn lanes(c: &mut Criterion) {
+    fn run<const N: usize>(group: &mut BenchmarkGroup<'_, WallTime>, inputs: &[[Fr; 2]]) {
+        let constants = CircomPoseidonConstants::shared();
+        let (chunks, _) = inputs.as_chunks::<N>();
+        group.bench_function(BenchmarkId::from_parameter(N), |b| {
+            b.iter(|| {
+                for chunk in chunks {
+                    black_box(poseidon_hash_lanes(constants, black_box(chunk)));
+                }
+            })
+        });
+    }
+
+    let inputs = inputs(1024);
+    let mut group = c.benchmark_group("lanes");
+    group.throughput(Throughput::Elements(inputs.len() as u64));
+    run::<1>(&mut group, &inputs);
+    run::<2>(&mut group, &inputs);
+    run::<4>(&mut group, &inputs);
+    run::<8>(&mut group, &inputs);
+    group.finish();
+}
+
+/// 64 BabyBear width-16 permutations, one at a time and `N` at a time on packed lanes.
+fn packed(c: &mut Criterion) {
+    fn run<const N: usize>(group: &mut BenchmarkGroup<'_, WallTime>, states: &[[BabyBear; 16]]) {
+        let parameters = babybear::plonky3_16();
+        let (chunks, _) = states.as_chunks::<N>();
+        group.bench_function(BenchmarkId::new("packed", N), |b| {
+            b.iter(|| {
+                for chunk in chunks {
+                    let mut chunk = *chunk;
+                    permute_packed(&parameters, black_box(&mut chunk));
+                    black_box(chunk);
+                }
+            })
+        });
+    }
+
+    let states: Vec<[BabyBear; 16]> = (0..64u32)
+        .map(|s| core::array::from_fn(|i| BabyBear::from(s * 16 + i as u32)))
+        .collect();
+    let mut group = c.benchmark_group("packed");
+    group.throughput(Throughput::Elements(states.len() as u64));
+    group.bench_function("scalar", |b| {
+        let parameters = babybear::plonky3_16();
+        b.iter(|| {
+            for state in &states {
+                let mut state = *state;
+                parameters.permute(black_box(&mut state));
+                black_box(state);
+            }
+        })
+    });
+    run::<4>(&mut group, &states);
+    run::<8>(&mut group, &states);
+    run::<16>(&mut group, &states);
+    group.finish();
+}
+
+/// One full-round MDS product, reduced once per lane against once per product.
+fn mix(c: &mut Criterion) {
+    let constants = CircomPoseidonConstants::shared();
+    let state = [Fr::from(1), Fr::from(2), -Fr::from(3)];
+
+    let mut group = c.benchmark_group("mix");
+    group.bench_function("lazy", |b| {
+        b.iter(|| mix::mix(black_box(&state), &constants.m))
+    });
+    group.bench_function("reduced", |b| {
+        b.iter(|| {
+            let state = black_box(&state);
+            let mut result = [Fr::default(); T];
+            for (val, row) in state.iter().zip(&constants.m) {
+                for (acc, m) in result.iter_mut().zip(row) {
+                    *acc += *val * m;
+                }
+            }
+            result
+        })
+    });
+    group.finish();
+}
+
+/// The S-box and the multiplication under it, against ark-ff's generic versions.
+fn sbox(c: &mut Criterion) {
+    let x = -Fr::from(3);
+
+    let mut group = c.benchmark_group("sbox");
+    group.bench_function("pow5", |b| b.iter(|| mont::pow5(black_box(&x))));
+    group.bench_function("pow", |b| b.iter(|| black_box(x).pow([5])));
+    group.bench_function("mul", |b| b.iter(|| mont::mul(black_box(&x), &x)));
+    group.bench_function("ark_mul", |b| b.iter(|| black_box(x) * x));
+    group.finish();
+}
+
+fn merkle(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merkle");
+    group.sample_size(20);
+    for size in MERKLE_LEAVES {
+        let leaves: Vec<Fr> = (0..size as u64).map(Fr::from).collect();
+        group.throughput(Throughput::Elements(size as u64 - 1));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &leaves, |b, leaves| {
+            b.iter(|| MerkleTree::new(black_box(leaves)))
+        });
+    }
+    group.finish();
+}
+
+/// The table is built at compile time; `default` copies it, `shared` borrows it.
+fn constants(c: &mut Criterion) {
+    let mut group = c.benchmark_group("constants");
+    group.bench_function("default", |b| b.iter(CircomPoseidonConstants::default));
+    group.bench_function("shared", |b| b.iter(CircomPoseidonConstants::shared));
+    group.bench_function("default_and_hash", |b| {
+        b.iter_batched(
+            || [Fr::from(1), Fr::from(2)],
+            |[x, y]| {
+                poseidon_hash_with_constants(
+                    &CircomPoseidonConstants::default(),
+                    Fr::default(),
+                    x,
+                    y,
+                )
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    hash,
+    permutation,
+    batch,
+    lanes,
+    packed,
+    mix,
+    sbox,
+    merkle,
+    constants
+);
+criterion_main!(benches);
@@ -0,0 +1,112 @@
+#ifndef RUST_POSEIDON_H
+#define RUST_POSEIDON_H
+
+/* Generated by cbindgen from capi/src/lib.rs; regenerate with `UPDATE_HEADER=1 cargo test -p rust-poseidon-capi`. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Number of inputs `poseidon_hash` takes.
+ */
+#define POSEIDON_ARITY 2
+
+/**
+ * Number of elements `poseidon_permutation` permutes.
+ */
+#define POSEIDON_WIDTH 3
+
+/**
+ * Size of a serialized field element.
+ */
+#define POSEIDON_ELEMENT_BYTES 32
+
+/**
+ * Most significant byte first, as circom, Solidity and `0x` hex print elements.
+ */
+#define POSEIDON_BIG_ENDIAN 0
+
+/**
+ * Least significant byte first, as arkworks and the circom witness format store them.
+ */
+#define POSEIDON_LITTLE_ENDIAN 1
+
+#define POSEIDON_OK 0
+
+/**
+ * The negative codes a function returns on error. An enum rather than constants so that
+ * the header gets enumerators, which C can use anywhere without parenthesizing `-1`.
+ */
+enum PoseidonError
+#ifdef __cplusplus
+  : int32_t
+#endif // __cplusplus
+ {
+  /**
+   * A pointer argument is null.
+   */
+  POSEIDON_ERROR_NULL_POINTER = -1,
+  /**
+   * `endianness` is neither `POSEIDON_BIG_ENDIAN` nor `POSEIDON_LITTLE_ENDIAN`.
+   */
+  POSEIDON_ERROR_ENDIANNESS = -2,
+  /**
+   * The number of inputs is not `POSEIDON_ARITY`.
+   */
+  POSEIDON_ERROR_ARITY = -3,
+  /**
+   * An input is not below the field order.
+   */
+  POSEIDON_ERROR_NON_CANONICAL = -4,
+};
+#ifndef __cplusplus
+typedef int32_t PoseidonError;
+#endif // __cplusplus
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * circomlib's `Poseidon(2)`: hashes `n_inputs` elements read from `inputs` and writes
+ * the result to `out`.
+ *
+ * # Safety
+ *
+ * `inputs` must point to `n_inputs` readable buffers and `out` to one writable buffer,
+ * or be null.
+ */
+int32_t poseidon_hash(const uint8_t (*inputs)[POSEIDON_ELEMENT_BYTES],
+                      size_t n_inputs,
+                      uint32_t endianness,
+                      uint8_t (*out)[POSEIDON_ELEMENT_BYTES]);
+
+/**
+ * circomlib's `PoseidonEx(2, 1)`: same as `poseidon_hash`, with the capacity element set
+ * to `initial_state` instead of zero.
+ *
+ * # Safety
+ *
+ * As for `poseidon_hash`; `initial_state` must point to one readable buffer, or be null.
+ */
+int32_t poseidon_hash_with_initial_state(const uint8_t (*initial_state)[POSEIDON_ELEMENT_BYTES],
+                                         const uint8_t (*inputs)[POSEIDON_ELEMENT_BYTES],
+                                         size_t n_inputs,
+                                         uint32_t endianness,
+                                         uint8_t (*out)[POSEIDON_ELEMENT_BYTES]);
+
+/**
+ * Runs the width-3 permutation in place on the `POSEIDON_WIDTH` buffers at `state`,
+ * `[capacity, x, y]`. Element 0 of the result is the hash of `x` and `y`.
+ *
+ * # Safety
+ *
+ * `state` must point to `POSEIDON_WIDTH` readable and writable buffers, or be null.
+ */
+int32_t poseidon_permutation(uint8_t (*state)[POSEIDON_ELEMENT_BYTES], uint32_t endianness);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUST_POSEIDON_H */
@@ -0,0 +1,170 @@
+//! C ABI for the circomlib Poseidon hash; `include/rust_poseidon.h` is generated from
+//! this file.
+//!
+//! Field elements cross the boundary as 32-byte buffers, big- or little-endian as the
+//! `endianness` argument says, and must be canonical, i.e. below the BN254 scalar field
+//! order. Every function returns `POSEIDON_OK` or one of the negative `POSEIDON_ERROR_*`
+//! codes of `PoseidonError`, and leaves its output untouched on error.
+
+use ark_ff::{BigInt, PrimeField};
+use rust_poseidon::{constants::T, field::Fr, functional};
+
+/// Number of inputs `poseidon_hash` takes.
+pub const POSEIDON_ARITY: usize = 2;
+/// Number of elements `poseidon_permutation` permutes.
+pub const POSEIDON_WIDTH: usize = 3;
+/// Size of a serialized field element.
+pub const POSEIDON_ELEMENT_BYTES: usize = 32;
+
+const _: () = assert!(POSEIDON_WIDTH == T && POSEIDON_ARITY + 1 == T);
+
+/// Most significant byte first, as circom, Solidity and `0x` hex print elements.
+pub const POSEIDON_BIG_ENDIAN: u32 = 0;
+/// Least significant byte first, as arkworks and the circom witness format store them.
+pub const POSEIDON_LITTLE_ENDIAN: u32 = 1;
+
+pub const POSEIDON_OK: i32 = 0;
+
+/// The negative codes a function returns on error. An enum rather than constants so that
+/// the header gets enumerators, which C can use anywhere without parenthesizing `-1`.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoseidonError {
+    /// A pointer argument is null.
+    NullPointer = -1,
+    /// `endianness` is neither `POSEIDON_BIG_ENDIAN` nor `POSEIDON_LITTLE_ENDIAN`.
+    Endianness = -2,
+    /// The number of inputs is not `POSEIDON_ARITY`.
+    Arity = -3,
+    /// An input is not below the field order.
+    NonCanonical = -4,
+}
+
+/// circomlib's `Poseidon(2)`: hashes `n_inputs` elements read from `inputs` and writes
+/// the result to `out`.
+///
+/// # Safety
+///
+/// `inputs` must point to `n_inputs` readable buffers and `out` to one writable buffer,
+/// or be null.
+#[no_mangle]
+pub unsafe extern "C" fn poseidon_hash(
+    inputs: *const [u8; POSEIDON_ELEMENT_BYTES],
+    n_inputs: usize,
+    endianness: u32,
+    out: *mut [u8; POSEIDON_ELEMENT_BYTES],
+) -> i32 {
+    let zero = [0; POSEIDON_ELEMENT_BYTES];
+    poseidon_hash_with_initial_state(&zero, inputs, n_inputs, endianness, out)
+}
+
+/// circomlib's `PoseidonEx(2, 1)`: same as `poseidon_hash`, with the capacity element set
+/// to `initial_state` instead of zero.
+///
+/// # Safety
+///
+/// As for `poseidon_hash`; `initial_state` must point to one readable buffer, or be null.
+#[no_mangle]
+pub unsafe extern "C" fn poseidon_hash_with_initial_state(
+    initial_state: *const [u8; POSEIDON_ELEMENT_BYTES],
+    inputs: *const [u8; POSEIDON_ELEMENT_BYTES],
+    n_inputs: usize,
+    endianness: u32,
+    out: *mut [u8; POSEIDON_ELEMENT_BYTES],
+) -> i32 {
+    status(|| {
+        let endianness = Endianness::new(endianness)?;
+        if initial_state.is_null() || inputs.is_null() || out.is_null() {
+            return Err(PoseidonError::NullPointer);
+        }
+        if n_inputs != POSEIDON_ARITY {
+            return Err(PoseidonError::Arity);
+        }
+        let [x, y] = *inputs.cast::<[[u8; POSEIDON_ELEMENT_BYTES]; POSEIDON_ARITY]>();
+
+        let result = functional::poseidon_hash_with_initial_state(
+            endianness.read(&*initial_state)?,
+            endianness.read(&x)?,
+            endianness.read(&y)?,
+        );
+        *out = endianness.write(result);
+        Ok(())
+    })
+}
+
+/// Runs the width-3 permutation in place on the `POSEIDON_WIDTH` buffers at `state`,
+/// `[capacity, x, y]`. Element 0 of the result is the hash of `x` and `y`.
+///
+/// # Safety
+///
+/// `state` must point to `POSEIDON_WIDTH` readable and writable buffers, or be null.
+#[no_mangle]
+pub unsafe extern "C" fn poseidon_permutation(
+    state: *mut [u8; POSEIDON_ELEMENT_BYTES],
+    endianness: u32,
+) -> i32 {
+    status(|| {
+        let endianness = Endianness::new(endianness)?;
+        let Some(state) = state
+            .cast::<[[u8; POSEIDON_ELEMENT_BYTES]; POSEIDON_WIDTH]>()
+            .as_mut()
+        else {
+            return Err(PoseidonError::NullPointer);
+        };
+
+        let mut elements = [Fr::from(0); T];
+        for (element, bytes) in elements.iter_mut().zip(state.iter()) {
+            *element = endianness.read(bytes)?;
+        }
+        *state =
+            functional::poseidon_permutation(elements).map(|element| endianness.write(element));
+        Ok(())
+    })
+}
+
+fn status(f: impl FnOnce() -> Result<(), PoseidonError>) -> i32 {
+    match f() {
+        Ok(()) => POSEIDON_OK,
+        Err(error) => error as i32,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Endianness {
+    Big,
+    Little,
+}
+
+impl Endianness {
+    fn new(value: u32) -> Result<Self, PoseidonError> {
+        match value {
+            POSEIDON_BIG_ENDIAN => Ok(Endianness::Big),
+            POSEIDON_LITTLE_ENDIAN => Ok(Endianness::Little),
+            _ => Err(PoseidonError::Endianness),
+        }
+    }
+
+    fn read(self, bytes: &[u8; POSEIDON_ELEMENT_BYTES]) -> Result<Fr, PoseidonError> {
+        let mut words = [0; 4];
+        for (i, chunk) in bytes.chunks_exact(8).enumerate() {
+            let chunk = chunk.try_into().unwrap();
+            match self {
+                Endianness::Big => words[3 - i] = u64::from_be_bytes(chunk),
+                Endianness::Little => words[i] = u64::from_le_bytes(chunk),
+            }
+        }
+        Fr::from_bigint(BigInt(words)).ok_or(PoseidonError::NonCanonical)
+    }
+
+    fn write(self, value: Fr) -> [u8; POSEIDON_ELEMENT_BYTES] {
+        let words = value.into_bigint().0;
+        let mut bytes = [0; POSEIDON_ELEMENT_BYTES];
+        for (i, chunk) in bytes.chunks_exact_mut(8).enumerate() {
+            match self {
+                Endianness::Big => chunk.copy_from_slice(&words[3 - i].to_be_bytes()),
+                Endianness::Little => chunk.copy_from_slice(&words[i].to_le_bytes()),
+            }
+        }
+        bytes
+    }
+}
@@ -0,0 +1,47 @@
+use std::{
+    env, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Compiles `tests/poseidon_test.c` against the header and the cdylib, then runs it.
+#[test]
+fn test_c_program() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // Cargo builds the cdylib for the test into `target/<profile>/deps`, next to the test binary.
+    let lib_dir: PathBuf = env::current_exe().unwrap().parent().unwrap().into();
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("poseidon_test");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(&compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg(root.join("tests/poseidon_test.c"))
+        .arg("-o")
+        .arg(&program)
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg("-lrust_poseidon_capi")
+        .status();
+    match status {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            eprintln!("skipping: no C compiler `{compiler}`");
+            return;
+        }
+        status => assert!(status.unwrap().success(), "compiling the C test failed"),
+    }
+
+    let output = Command::new(&program)
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .env("DYLD_LIBRARY_PATH", &lib_dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
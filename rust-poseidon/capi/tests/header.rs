@@ -0,0 +1,27 @@
+use std::{env, fs, path::Path};
+
+/// Keeps the committed header in sync with `src/lib.rs`. Run with `UPDATE_HEADER=1` to
+/// rewrite it after changing the API.
+#[test]
+fn test_header_is_up_to_date() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(root.join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(root.join("src/lib.rs"))
+        .generate()
+        .unwrap()
+        .write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    let path = root.join("include/rust_poseidon.h");
+    if env::var_os("UPDATE_HEADER").is_some() {
+        fs::write(&path, &generated).unwrap();
+    }
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        generated,
+        "include/rust_poseidon.h is stale; rerun with UPDATE_HEADER=1"
+    );
+}
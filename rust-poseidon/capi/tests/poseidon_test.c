@@ -0,0 +1,96 @@
+/* Checks the C ABI against `test_functional_poseidon` in src/functional.rs. */
+#include <stdio.h>
+#include <string.h>
+
+#include "rust_poseidon.h"
+
+/* x, y and poseidon_hash(x, y), big-endian. */
+static const uint8_t X[POSEIDON_ELEMENT_BYTES] = {
+    0x0a, 0xe0, 0x3f, 0xeb, 0x01, 0x83, 0xf5, 0x3f, 0x78, 0x4a, 0xcd, 0x35, 0xa2, 0xff, 0x42, 0x07,
+    0xa3, 0x85, 0xf2, 0x47, 0xf5, 0x4a, 0x02, 0x22, 0x93, 0x5b, 0x6e, 0x48, 0xac, 0x68, 0x10, 0x99,
+};
+static const uint8_t Y[POSEIDON_ELEMENT_BYTES] = {
+    0x0b, 0x9d, 0x27, 0x82, 0xb3, 0xe0, 0xa9, 0x92, 0xc8, 0x47, 0x20, 0xd5, 0x08, 0x1a, 0x7a, 0x9b,
+    0x3f, 0x84, 0x2c, 0xe1, 0xc6, 0xa2, 0xf9, 0x9c, 0xc8, 0x00, 0xd8, 0x99, 0x8b, 0x5e, 0x13, 0x03,
+};
+static const uint8_t EXPECTED[POSEIDON_ELEMENT_BYTES] = {
+    0x0a, 0x9f, 0x13, 0x58, 0x4e, 0x92, 0x8b, 0x81, 0x37, 0xac, 0x0c, 0x4f, 0x9f, 0xd3, 0x72, 0xd8,
+    0xd4, 0xc4, 0xea, 0xa2, 0xa7, 0x92, 0xf4, 0xe4, 0x04, 0x3f, 0xa6, 0x42, 0x52, 0x8f, 0x49, 0xee,
+};
+/* The field order, the smallest non-canonical value. */
+static const uint8_t MODULUS[POSEIDON_ELEMENT_BYTES] = {
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+};
+
+static int failures = 0;
+
+#define CHECK(condition)                                                   \
+    do {                                                                   \
+        if (!(condition)) {                                                \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #condition);                                           \
+            failures++;                                                    \
+        }                                                                  \
+    } while (0)
+
+static void reverse(uint8_t *out, const uint8_t *in) {
+    for (size_t i = 0; i < POSEIDON_ELEMENT_BYTES; i++) {
+        out[i] = in[POSEIDON_ELEMENT_BYTES - 1 - i];
+    }
+}
+
+int main(void) {
+    uint8_t inputs[POSEIDON_ARITY][POSEIDON_ELEMENT_BYTES];
+    uint8_t out[POSEIDON_ELEMENT_BYTES];
+    uint8_t expected[POSEIDON_ELEMENT_BYTES];
+
+    memcpy(inputs[0], X, sizeof X);
+    memcpy(inputs[1], Y, sizeof Y);
+    CHECK(poseidon_hash(inputs, POSEIDON_ARITY, POSEIDON_BIG_ENDIAN, &out) == POSEIDON_OK);
+    CHECK(memcmp(out, EXPECTED, sizeof out) == 0);
+
+    reverse(inputs[0], X);
+    reverse(inputs[1], Y);
+    reverse(expected, EXPECTED);
+    CHECK(poseidon_hash(inputs, POSEIDON_ARITY, POSEIDON_LITTLE_ENDIAN, &out) == POSEIDON_OK);
+    CHECK(memcmp(out, expected, sizeof out) == 0);
+
+    uint8_t zero[POSEIDON_ELEMENT_BYTES] = {0};
+    CHECK(poseidon_hash_with_initial_state(&zero, inputs, POSEIDON_ARITY, POSEIDON_LITTLE_ENDIAN,
+                                           &out) == POSEIDON_OK);
+    CHECK(memcmp(out, expected, sizeof out) == 0);
+
+    uint8_t state[POSEIDON_WIDTH][POSEIDON_ELEMENT_BYTES] = {{0}};
+    memcpy(state[1], X, sizeof X);
+    memcpy(state[2], Y, sizeof Y);
+    CHECK(poseidon_permutation(state, POSEIDON_BIG_ENDIAN) == POSEIDON_OK);
+    CHECK(memcmp(state[0], EXPECTED, sizeof EXPECTED) == 0);
+
+    /* Errors leave the output untouched. */
+    memset(out, 0xaa, sizeof out);
+    memcpy(inputs[0], MODULUS, sizeof MODULUS);
+    CHECK(poseidon_hash(inputs, POSEIDON_ARITY, POSEIDON_BIG_ENDIAN, &out) ==
+          POSEIDON_ERROR_NON_CANONICAL);
+    CHECK(poseidon_hash(inputs, 1, POSEIDON_BIG_ENDIAN, &out) == POSEIDON_ERROR_ARITY);
+    CHECK(poseidon_hash(inputs, POSEIDON_ARITY, 2, &out) == POSEIDON_ERROR_ENDIANNESS);
+    CHECK(poseidon_hash(NULL, POSEIDON_ARITY, POSEIDON_BIG_ENDIAN, &out) ==
+          POSEIDON_ERROR_NULL_POINTER);
+    CHECK(poseidon_hash(inputs, POSEIDON_ARITY, POSEIDON_BIG_ENDIAN, NULL) ==
+          POSEIDON_ERROR_NULL_POINTER);
+    CHECK(out[0] == 0xaa && out[POSEIDON_ELEMENT_BYTES - 1] == 0xaa);
+    /* The codes are usable in any expression, e.g. negated. */
+    CHECK(-POSEIDON_ERROR_ARITY == 3 && 1 - POSEIDON_ERROR_NULL_POINTER == 2);
+
+    uint8_t before[POSEIDON_WIDTH][POSEIDON_ELEMENT_BYTES];
+    memcpy(state[2], MODULUS, sizeof MODULUS);
+    memcpy(before, state, sizeof state);
+    CHECK(poseidon_permutation(state, POSEIDON_BIG_ENDIAN) == POSEIDON_ERROR_NON_CANONICAL);
+    CHECK(memcmp(before, state, sizeof state) == 0);
+    CHECK(poseidon_permutation(NULL, POSEIDON_BIG_ENDIAN) == POSEIDON_ERROR_NULL_POINTER);
+
+    if (failures == 0) {
+        printf("all checks passed\n");
+    }
+    return failures == 0 ? 0 : 1;
+}
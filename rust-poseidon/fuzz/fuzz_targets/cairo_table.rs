@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_poseidon::cairo::parse_constant_table;
+
+fuzz_target!(|source: &str| {
+    let _ = parse_constant_table(source);
+});
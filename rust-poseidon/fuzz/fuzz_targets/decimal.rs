@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_poseidon::field::decimal;
+
+fuzz_target!(|text: &str| {
+    if let Some(value) = decimal::from_str(text) {
+        assert_eq!(decimal::from_str(&decimal::to_string(&value)), Some(value));
+    }
+});
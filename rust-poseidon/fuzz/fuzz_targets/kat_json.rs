@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_poseidon::{
+    kat::{Capture, VectorFile},
+    trace::Trace,
+};
+
+fuzz_target!(|json: &str| {
+    if let Ok(vectors) = VectorFile::from_json(json) {
+        let reparsed = VectorFile::from_json(&serde_json::to_string(&vectors).unwrap());
+        assert_eq!(reparsed.unwrap(), vectors);
+    }
+    if let Ok(trace) = Trace::from_json(json) {
+        assert_eq!(Trace::from_json(&trace.to_json()).unwrap(), trace);
+    }
+    let _ = Capture::from_python_output(json);
+});
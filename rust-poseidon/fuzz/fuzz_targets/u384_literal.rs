@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_poseidon::{field::Fr, u384::U384};
+
+fuzz_target!(|text: &str| {
+    let Ok(value) = text.parse::<U384>() else {
+        return;
+    };
+    assert_eq!(value.to_string().parse::<U384>(), Ok(value));
+    assert_eq!(value.to_decimal_literal().parse::<U384>(), Ok(value));
+    if let Ok(fr) = Fr::try_from(value) {
+        assert_eq!(U384::from(fr), value);
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_poseidon::wtns::{parse_wtns, write_wtns};
+
+fuzz_target!(|bytes: &[u8]| {
+    if let Ok(witness) = parse_wtns(bytes) {
+        assert_eq!(parse_wtns(&write_wtns(&witness)), Ok(witness));
+    }
+});
@@ -0,0 +1,108 @@
+//! Python bindings, importable as `rust_poseidon`.
+//!
+//! The functions mirror `poseidon.py`: inputs are Python ints, reduced modulo the BN254
+//! scalar field order like `poseidon.py` does, and results are ints in `[0, p)`. The
+//! constant tables are exported under `poseidon.py`'s names, so scripts can import them
+//! instead of keeping their own copy.
+
+use ark_ff::PrimeField;
+use num_bigint::{BigInt, BigUint, Sign};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use rust_poseidon::{
+    constants::{CircomPoseidonConstants, T},
+    field::Fr,
+    functional::{poseidon_hash_batch, poseidon_hash_with_initial_state, poseidon_permutation},
+};
+
+/// Reduces an integer modulo p, as `poseidon.py`'s `% CIRCOM_P` does for negative values too.
+pub fn to_fr(value: BigInt) -> Fr {
+    let (sign, magnitude) = value.into_parts();
+    let value = Fr::from(magnitude);
+    if sign == Sign::Minus {
+        -value
+    } else {
+        value
+    }
+}
+
+pub fn to_int(value: Fr) -> BigUint {
+    value.into()
+}
+
+fn to_array<const N: usize>(values: Vec<BigInt>, what: &str) -> PyResult<[Fr; N]> {
+    let len = values.len();
+    let values: Vec<Fr> = values.into_iter().map(to_fr).collect();
+    values
+        .try_into()
+        .map_err(|_| PyValueError::new_err(format!("expected {N} {what}, got {len}")))
+}
+
+/// circomlib's `PoseidonEx(2, 1)` on two inputs, with the capacity element set to
+/// `initial_state`. Same as `poseidon.py`'s `poseidon_hash(a, b, initial_state)`.
+#[pyfunction]
+#[pyo3(signature = (inputs, initial_state = BigInt::ZERO))]
+fn poseidon_hash(inputs: Vec<BigInt>, initial_state: BigInt) -> PyResult<BigUint> {
+    let [x, y] = to_array(inputs, "inputs")?;
+    Ok(to_int(poseidon_hash_with_initial_state(
+        to_fr(initial_state),
+        x,
+        y,
+    )))
+}
+
+/// The width-3 permutation on `[initial_state, x, y]`; element 0 of the result is the hash.
+#[pyfunction]
+fn permutation(state: Vec<BigInt>) -> PyResult<Vec<BigUint>> {
+    let state = to_array::<T>(state, "state elements")?;
+    Ok(poseidon_permutation(state)
+        .into_iter()
+        .map(to_int)
+        .collect())
+}
+
+/// Hashes every `(x, y)` pair of `inputs` with a zero initial state. The hashing runs
+/// without the GIL.
+#[pyfunction(name = "poseidon_hash_batch")]
+fn hash_batch(py: Python<'_>, inputs: Vec<(BigInt, BigInt)>) -> Vec<BigUint> {
+    let inputs: Vec<[Fr; 2]> = inputs
+        .into_iter()
+        .map(|(x, y)| [to_fr(x), to_fr(y)])
+        .collect();
+    py.allow_threads(|| poseidon_hash_batch(&inputs))
+        .into_iter()
+        .map(to_int)
+        .collect()
+}
+
+#[pymodule]
+#[pyo3(name = "rust_poseidon")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let constants = CircomPoseidonConstants::shared();
+    let vector = |values: &[Fr]| values.iter().copied().map(to_int).collect::<Vec<_>>();
+    let matrix = |rows: &[[Fr; T]; T]| rows.iter().map(|row| vector(row)).collect::<Vec<_>>();
+
+    m.add("CIRCOM_P", BigUint::from(Fr::MODULUS))?;
+    m.add("POSEIDON_C", vector(&constants.c))?;
+    m.add("POSEIDON_M", matrix(&constants.m))?;
+    m.add("POSEIDON_P", matrix(&constants.p))?;
+    m.add("POSEIDON_S", vector(&constants.s))?;
+    m.add_function(wrap_pyfunction!(poseidon_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(permutation, m)?)?;
+    m.add_function(wrap_pyfunction!(hash_batch, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_fr_reduces_like_python() {
+        let p = BigInt::from(to_int(-Fr::from(1))) + 1;
+        assert_eq!(to_fr(BigInt::from(2)), Fr::from(2));
+        assert_eq!(to_fr(&p + 2), Fr::from(2));
+        assert_eq!(to_fr(BigInt::from(-1)), -Fr::from(1));
+        assert_eq!(to_fr(-&p * 3 - 1), -Fr::from(1));
+        assert_eq!(to_int(to_fr(&p - 1)), BigUint::from(Fr::MODULUS) - 1u32);
+    }
+}
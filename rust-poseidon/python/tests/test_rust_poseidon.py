@@ -0,0 +1,77 @@
+"""Checks the extension module against `poseidon.py`.
+
+    maturin develop --release -m rust-poseidon/python/Cargo.toml
+    python -m unittest discover rust-poseidon/python/tests
+"""
+import os
+import random
+import sys
+import unittest
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "..", ".."))
+
+import poseidon as reference  # noqa: E402  (the repository's poseidon.py)
+import rust_poseidon  # noqa: E402
+
+
+class RustPoseidonTest(unittest.TestCase):
+    def setUp(self):
+        self.random = random.Random(0)
+
+    def element(self):
+        return self.random.randrange(reference.CIRCOM_P)
+
+    def test_known_answer(self):
+        self.assertEqual(
+            rust_poseidon.poseidon_hash([1, 2]),
+            7853200120776062878684798364095072458815029376092732009249414926327459813530,
+        )
+
+    def test_matches_poseidon_py(self):
+        for _ in range(32):
+            a, b, initial_state = self.element(), self.element(), self.element()
+            self.assertEqual(
+                rust_poseidon.poseidon_hash([a, b], initial_state),
+                reference.poseidon_hash(a, b, initial_state),
+            )
+
+    def test_reduces_like_poseidon_py(self):
+        p = reference.CIRCOM_P
+        for a, b in [(p, 1), (-1, 2), (3 * p + 5, -p - 7), (2**300, -(2**300))]:
+            self.assertEqual(
+                rust_poseidon.poseidon_hash([a, b], initial_state=-1),
+                reference.poseidon_hash(a, b, initial_state=-1),
+            )
+
+    def test_permutation(self):
+        state = [self.element() for _ in range(3)]
+        result = rust_poseidon.permutation(state)
+        self.assertEqual(len(result), 3)
+        self.assertEqual(result[0], reference.poseidon_hash(state[1], state[2], state[0]))
+
+    def test_batch(self):
+        pairs = [(self.element(), self.element()) for _ in range(9)]
+        self.assertEqual(
+            rust_poseidon.poseidon_hash_batch(pairs),
+            [reference.poseidon_hash(a, b) for a, b in pairs],
+        )
+        self.assertEqual(rust_poseidon.poseidon_hash_batch([]), [])
+
+    def test_constants(self):
+        self.assertEqual(rust_poseidon.CIRCOM_P, reference.CIRCOM_P)
+        self.assertEqual(rust_poseidon.POSEIDON_C, reference.POSEIDON_C)
+        self.assertEqual(rust_poseidon.POSEIDON_M, reference.POSEIDON_M)
+        self.assertEqual(rust_poseidon.POSEIDON_P, reference.POSEIDON_P)
+        self.assertEqual(rust_poseidon.POSEIDON_S, reference.POSEIDON_S)
+
+    def test_rejects_bad_input(self):
+        with self.assertRaises(ValueError):
+            rust_poseidon.poseidon_hash([1, 2, 3])
+        with self.assertRaises(ValueError):
+            rust_poseidon.permutation([1, 2])
+        with self.assertRaises(TypeError):
+            rust_poseidon.poseidon_hash(["1", 2])
+
+
+if __name__ == "__main__":
+    unittest.main()
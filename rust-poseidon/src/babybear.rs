@@ -0,0 +1,367 @@
+//! Poseidon2 over the 31-bit BabyBear field, as STARK provers use it.
+//!
+//! `BabyBear` is the field modulo `2^31 - 2^27 + 1` on the `small_field` backend: elements
+//! are canonical values in a `u64` limb and a product is reduced with one `u64`
+//! remainder. `packed::permute_packed` permutes several states at once on Montgomery
+//! `u32` lanes instead. It is an arkworks `PrimeField`, so the generic Poseidon2 permutation runs on it.
+//!
+//! The instances have `x^7` and 8 full rounds, with 13 partial rounds at width 16 and 21
+//! at width 24, and all of them use the round constants published with the Poseidon2
+//! reference implementation. `poseidon2_16` and `poseidon2_24` also use the reference's
+//! matrices, as RISC Zero's width-24 hash does. `plonky3_16` and `plonky3_24` are
+//! Plonky3's default instances, which mix with `M4::Plonky3` and their own internal
+//! diagonals. The tables are converted at compile time, so this builds without `std`.
+
+use crate::{
+    packed::{monty_from_canonical, monty_reduce, Prime31},
+    poseidon2::{Poseidon2Parameters, M4},
+    small_field::{elements, rows, SmallField, SmallFp, SmallPrime},
+};
+
+/// `2^31 - 2^27 + 1`.
+pub const MODULUS: u32 = 0x7800_0001;
+
+/// `MODULUS^-1 mod 2^32`, for the Montgomery lanes of `packed`.
+pub const MU: u32 = 0x8800_0001;
+
+pub struct BabyBearPrime;
+
+impl SmallPrime for BabyBearPrime {
+    const MODULUS: u64 = MODULUS as u64;
+    const GENERATOR: u64 = 31;
+    const TWO_ADICITY: u32 = 27;
+    const TWO_ADIC_ROOT_OF_UNITY: u64 = 0x1a42_7a41;
+
+    fn mul(a: u64, b: u64) -> u64 {
+        a * b % Self::MODULUS
+    }
+}
+
+impl Prime31 for BabyBearPrime {
+    fn to_lane(value: u32) -> u32 {
+        monty_from_canonical(value, MODULUS)
+    }
+
+    fn from_lane(lane: u32) -> u32 {
+        monty_reduce(lane as u64, MODULUS, MU)
+    }
+
+    fn mul_lanes(a: u32, b: u32) -> u32 {
+        monty_reduce(a as u64 * b as u64, MODULUS, MU)
+    }
+}
+
+pub type BabyBearConfig = SmallField<BabyBearPrime>;
+pub type BabyBear = SmallFp<BabyBearPrime>;
+
+pub const ALPHA: u64 = 7;
+pub const ROUNDS_F: usize = 8;
+pub const ROUNDS_P_16: usize = 13;
+pub const ROUNDS_P_24: usize = 21;
+
+/// The width-16 constants of the full rounds, first and last four.
+#[rustfmt::skip]
+pub const EXTERNAL_16: [[u32; 16]; ROUNDS_F] = [
+    [
+        0x69cbb6af, 0x46ad93f9, 0x60a00f4e, 0x6b1297cd, 0x23189afe, 0x732e7bef, 0x72c246de, 0x2c941900,
+        0x0557eede, 0x1580496f, 0x3a3ea77b, 0x54f3f271, 0x0f49b029, 0x47872fe1, 0x221e2e36, 0x1ab7202e,
+    ],
+    [
+        0x487779a6, 0x3851c9d8, 0x38dc17c0, 0x209f8849, 0x268dcee8, 0x350c48da, 0x5b9ad32e, 0x0523272b,
+        0x3f89055b, 0x01e894b2, 0x13ddedde, 0x1b2ef334, 0x7507d8b4, 0x6ceeb94e, 0x52eb6ba2, 0x50642905,
+    ],
+    [
+        0x05453f3f, 0x06349efc, 0x6922787c, 0x04bfff9c, 0x768c714a, 0x3e9ff21a, 0x15737c9c, 0x2229c807,
+        0x0d47f88c, 0x097e0ecc, 0x27eadba0, 0x2d7d29e4, 0x3502aaa0, 0x0f475fd7, 0x29fbda49, 0x018afffd,
+    ],
+    [
+        0x0315b618, 0x6d4497d1, 0x1b171d9e, 0x52861abd, 0x2e5d0501, 0x3ec8646c, 0x6e5f250a, 0x148ae8e6,
+        0x17f5fa4a, 0x3e66d284, 0x0051aa3b, 0x483f7913, 0x2cfe5f15, 0x023427ca, 0x2cc78315, 0x1e36ea47,
+    ],
+    [
+        0x7290a80d, 0x6f7e5329, 0x598ec8a8, 0x76a859a0, 0x6559e868, 0x657b83af, 0x13271d3f, 0x1f876063,
+        0x0aeeae37, 0x706e9ca6, 0x46400cee, 0x72a05c26, 0x2c589c9e, 0x20bd37a7, 0x6a2d3d10, 0x20523767,
+    ],
+    [
+        0x5b8fe9c4, 0x2aa501d6, 0x1e01ac3e, 0x1448bc54, 0x5ce5ad1c, 0x4918a14d, 0x2c46a83f, 0x4fcf6876,
+        0x61d8d5c8, 0x6ddf4ff9, 0x11fda4d3, 0x02933a8f, 0x170eaf81, 0x5a9c314f, 0x49a12590, 0x35ec52a1,
+    ],
+    [
+        0x58eb1611, 0x5e481e65, 0x367125c9, 0x0eba33ba, 0x1fc28ded, 0x066399ad, 0x0cbec0ea, 0x75fd1af0,
+        0x50f5bf4e, 0x643d5f41, 0x6f4fe718, 0x5b3cbbde, 0x1e3afb3e, 0x296fb027, 0x45e1547b, 0x4a8db2ab,
+    ],
+    [
+        0x59986d19, 0x30bcdfa3, 0x1db63932, 0x1d7c2824, 0x53b33681, 0x0673b747, 0x038a98a3, 0x2c5bce60,
+        0x351979cd, 0x5008fb73, 0x547bca78, 0x711af481, 0x3f93bf64, 0x644d987b, 0x3c8bcd87, 0x608758b8,
+    ],
+];
+
+/// The width-16 constants of the partial rounds.
+#[rustfmt::skip]
+pub const INTERNAL_16: [u32; ROUNDS_P_16] = [
+    0x5a8053c0, 0x693be639, 0x3858867d, 0x19334f6b, 0x128f0fd8, 0x4e2b1ccb, 0x61210ce0, 0x3c318939,
+    0x0b5b2f22, 0x2edb11d5, 0x213effdf, 0x0cac4606, 0x241af16d,
+];
+
+/// The width-16 internal matrix minus the all-ones matrix.
+#[rustfmt::skip]
+pub const DIAGONAL_16: [u32; 16] = [
+    0x0a632d94, 0x6db657b7, 0x56fbdc9e, 0x052b3d8a, 0x33745201, 0x5c03108c, 0x0beba37b, 0x258c2e8b,
+    0x12029f39, 0x694909ce, 0x6d231724, 0x21c3b222, 0x3c0904a5, 0x01d6acda, 0x27705c83, 0x5231c802,
+];
+
+/// The width-24 constants of the full rounds, first and last four.
+#[rustfmt::skip]
+pub const EXTERNAL_24: [[u32; 24]; ROUNDS_F] = [
+    [
+        0x0fa20c37, 0x0795bb97, 0x12c60b9c, 0x0eabd88e, 0x096485ca, 0x07093527, 0x1b1d4e50, 0x30a01ace,
+        0x3bd86f5a, 0x69af7c28, 0x3f94775f, 0x731560e8, 0x465a0ecd, 0x574ef807, 0x62fd4870, 0x52ccfe44,
+        0x14772b14, 0x4dedf371, 0x260acd7c, 0x1f51dc58, 0x75125532, 0x686a4d7b, 0x54bac179, 0x31947706,
+    ],
+    [
+        0x29799d3b, 0x6e01ae90, 0x203a7a64, 0x4f7e25be, 0x72503f77, 0x45bd3b69, 0x769bd6b4, 0x5a867f08,
+        0x4fdba082, 0x251c4318, 0x28f06201, 0x6788c43a, 0x4c6d6a99, 0x357784a8, 0x2abaf051, 0x770f7de6,
+        0x1794b784, 0x4796c57a, 0x724b7a10, 0x449989a7, 0x64935cf1, 0x59e14aac, 0x0e620bb8, 0x3af5a33b,
+    ],
+    [
+        0x4465cc0e, 0x019df68f, 0x4af8d068, 0x08784f82, 0x0cefdeae, 0x6337a467, 0x32fa7a16, 0x486f62d6,
+        0x386a7480, 0x20f17c4a, 0x54e50da8, 0x2012cf03, 0x5fe52950, 0x09afb6cd, 0x2523044e, 0x5c54d0ef,
+        0x71c01f3c, 0x60b2c4fb, 0x4050b379, 0x5e6a70a5, 0x418543f5, 0x71debe56, 0x1aad2994, 0x3368a483,
+    ],
+    [
+        0x07a86f3a, 0x5ea43ff1, 0x2443780e, 0x4ce444f7, 0x146f9882, 0x3132b089, 0x197ea856, 0x667030c3,
+        0x2317d5dc, 0x0c2c48a7, 0x56b2df66, 0x67bd81e9, 0x4fcdfb19, 0x4baaef32, 0x0328d30a, 0x6235760d,
+        0x12432912, 0x0a49e258, 0x030e1b70, 0x48caeb03, 0x49e4d9e9, 0x1051b5c6, 0x6a36dbbe, 0x4cff27a5,
+    ],
+    [
+        0x032959ad, 0x2b18af6a, 0x55d3dc8c, 0x43bd26c8, 0x0c41595f, 0x7048d2e2, 0x00db8983, 0x2af563d7,
+        0x6e84758f, 0x611d64e1, 0x1f9977e2, 0x64163a0a, 0x5c5fc27b, 0x02e22561, 0x3a2d75db, 0x1ba7b71a,
+        0x34343f64, 0x7406b35d, 0x19df8299, 0x6ff4480a, 0x514a81c8, 0x57ab52ce, 0x6ad69f52, 0x3e0c0e0d,
+    ],
+    [
+        0x48126114, 0x2a9d62cc, 0x17441f23, 0x485762bb, 0x2f218674, 0x06fdc64a, 0x0861b7f2, 0x3b36eee6,
+        0x70a11040, 0x04b31737, 0x3722a872, 0x2a351c63, 0x623560dc, 0x62584ab2, 0x382c7c04, 0x3bf9edc7,
+        0x0e38fe51, 0x376f3b10, 0x5381e178, 0x3afc61c7, 0x5c1bcb4d, 0x6643ce1f, 0x2d0af1c1, 0x08f583cc,
+    ],
+    [
+        0x5d6ff60f, 0x6324c1e5, 0x74412fb7, 0x70c0192e, 0x0b72f141, 0x4067a111, 0x57388c4f, 0x351009ec,
+        0x0974c159, 0x539a58b3, 0x038c0cff, 0x476c0392, 0x3f7bc15f, 0x4491dd2c, 0x4d1fef55, 0x04936ae3,
+        0x58214dd4, 0x683c6aad, 0x1b42f16b, 0x6dc79135, 0x2d4e71ec, 0x3e2946ea, 0x59dce8db, 0x6cee892a,
+    ],
+    [
+        0x47f07350, 0x7106ce93, 0x3bd4a7a9, 0x2bfe636a, 0x430011e9, 0x001cd66a, 0x307faf5b, 0x0d9ef3fe,
+        0x6d40043a, 0x2e8f470c, 0x1b6865e8, 0x0c0e6c01, 0x4d41981f, 0x423b9d3d, 0x410408cc, 0x263f0884,
+        0x5311bbd0, 0x4dae58d8, 0x30401cea, 0x09afa575, 0x4b3d5b42, 0x63ac0b37, 0x5fe5bb14, 0x5244e9d4,
+    ],
+];
+
+/// The width-24 constants of the partial rounds.
+#[rustfmt::skip]
+pub const INTERNAL_24: [u32; ROUNDS_P_24] = [
+    0x1da78ec2, 0x730b0924, 0x3eb56cf3, 0x5bd93073, 0x37204c97, 0x51642d89, 0x66e943e8, 0x1a3e72de,
+    0x70beb1e9, 0x30ff3b3f, 0x4240d1c4, 0x12647b8d, 0x65d86965, 0x49ef4d7c, 0x47785697, 0x46b3969f,
+    0x5c7b7a0e, 0x7078fc60, 0x4f22d482, 0x482a9aee, 0x6beb839d,
+];
+
+/// The width-24 internal matrix minus the all-ones matrix.
+#[rustfmt::skip]
+pub const DIAGONAL_24: [u32; 24] = [
+    0x409133f0, 0x1667a8a1, 0x06a6c7b6, 0x6f53160e, 0x273b11d1, 0x03176c5d, 0x72f9bbf9, 0x73ceba91,
+    0x5cdef81d, 0x01393285, 0x46daee06, 0x065d7ba6, 0x52d72d6f, 0x05dd05e0, 0x3bab4b63, 0x6ada3842,
+    0x2fc5fbec, 0x770d61b0, 0x5715aae9, 0x03ef0e90, 0x75b6c770, 0x242adf5f, 0x00d0ca4c, 0x36c0e388,
+];
+
+/// Plonky3's width-16 internal diagonal,
+/// `[-2, 1, 2, 1/2, 3, 4, -1/2, -3, -4, 1/2^8, 1/4, 1/8, 1/2^27, -1/2^8, -1/16, -1/2^27]`.
+#[rustfmt::skip]
+pub const PLONKY3_DIAGONAL_16: [u32; 16] = [
+    0x77ffffff, 0x00000001, 0x00000002, 0x3c000001, 0x00000003, 0x00000004, 0x3c000000, 0x77fffffe,
+    0x77fffffd, 0x77880001, 0x5a000001, 0x69000001, 0x77fffff2, 0x00780000, 0x07800000, 0x0000000f,
+];
+
+/// Plonky3's width-24 internal diagonal, `[-2, 1, 2, 1/2, 3, 4, -1/2, -3, -4, 1/2^8, 1/4,
+/// 1/8, 1/16, 1/2^7, 1/2^9, 1/2^27, -1/2^8, -1/4, -1/8, -1/16, -1/32, -1/64, -1/2^7, -1/2^27]`.
+#[rustfmt::skip]
+pub const PLONKY3_DIAGONAL_24: [u32; 24] = [
+    0x77ffffff, 0x00000001, 0x00000002, 0x3c000001, 0x00000003, 0x00000004, 0x3c000000, 0x77fffffe,
+    0x77fffffd, 0x77880001, 0x5a000001, 0x69000001, 0x70800001, 0x77100001, 0x77c40001, 0x77fffff2,
+    0x00780000, 0x1e000000, 0x0f000000, 0x07800000, 0x03c00000, 0x01e00000, 0x00f00000, 0x0000000f,
+];
+
+static EXTERNAL_16_ELEMENTS: [[BabyBear; 16]; ROUNDS_F] = rows(&EXTERNAL_16);
+static INTERNAL_16_ELEMENTS: [BabyBear; ROUNDS_P_16] = elements(&INTERNAL_16);
+static EXTERNAL_24_ELEMENTS: [[BabyBear; 24]; ROUNDS_F] = rows(&EXTERNAL_24);
+static INTERNAL_24_ELEMENTS: [BabyBear; ROUNDS_P_24] = elements(&INTERNAL_24);
+
+fn width_16(diagonal: &'static [BabyBear; 16]) -> Poseidon2Parameters<'static, BabyBear, 16> {
+    Poseidon2Parameters::new(
+        ALPHA,
+        ROUNDS_F,
+        ROUNDS_P_16,
+        &EXTERNAL_16_ELEMENTS,
+        &INTERNAL_16_ELEMENTS,
+        diagonal,
+    )
+}
+
+fn width_24(diagonal: &'static [BabyBear; 24]) -> Poseidon2Parameters<'static, BabyBear, 24> {
+    Poseidon2Parameters::new(
+        ALPHA,
+        ROUNDS_F,
+        ROUNDS_P_24,
+        &EXTERNAL_24_ELEMENTS,
+        &INTERNAL_24_ELEMENTS,
+        diagonal,
+    )
+}
+
+/// The width-16 permutation with the reference's matrices.
+pub fn poseidon2_16() -> Poseidon2Parameters<'static, BabyBear, 16> {
+    static DIAGONAL: [BabyBear; 16] = elements(&DIAGONAL_16);
+    width_16(&DIAGONAL)
+}
+
+/// The width-24 permutation with the reference's matrices.
+pub fn poseidon2_24() -> Poseidon2Parameters<'static, BabyBear, 24> {
+    static DIAGONAL: [BabyBear; 24] = elements(&DIAGONAL_24);
+    width_24(&DIAGONAL)
+}
+
+/// Plonky3's `default_babybear_poseidon2_16`.
+pub fn plonky3_16() -> Poseidon2Parameters<'static, BabyBear, 16> {
+    static DIAGONAL: [BabyBear; 16] = elements(&PLONKY3_DIAGONAL_16);
+    width_16(&DIAGONAL).with_m4(M4::Plonky3)
+}
+
+/// Plonky3's `default_babybear_poseidon2_24`.
+pub fn plonky3_24() -> Poseidon2Parameters<'static, BabyBear, 24> {
+    static DIAGONAL: [BabyBear; 24] = elements(&PLONKY3_DIAGONAL_24);
+    width_24(&DIAGONAL).with_m4(M4::Plonky3)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::Field;
+
+    use super::*;
+    use crate::{permutation::Permutation, poseidon2::assert_grain_constants, small_field::dyadic};
+
+    #[test]
+    fn test_generated_constants() {
+        assert_grain_constants(&poseidon2_16());
+        assert_grain_constants(&poseidon2_24());
+    }
+
+    #[test]
+    fn test_known_answer() {
+        // The width-24 vector of the Poseidon2 reference implementation, which RISC Zero's
+        // tests also check: the permutation of [0, 1, ..., 23].
+        let mut state = core::array::from_fn(|i| BabyBear::from(i as u32));
+        poseidon2_24().permute(&mut state);
+        let expected: [u32; 24] = [
+            0x2ed3e23d, 0x12921fb0, 0x0e659e79, 0x61d81dc9, 0x32bae33b, 0x62486ae3, 0x1e681b60,
+            0x24b91325, 0x2a2ef5b9, 0x50e8593e, 0x5bc818ec, 0x10691997, 0x35a14520, 0x2ba6a3c5,
+            0x279d47ec, 0x55014e81, 0x5953a67f, 0x2f403111, 0x6b8828ff, 0x1801301f, 0x2749207a,
+            0x3dc9cf21, 0x3c985ba2, 0x57a99864,
+        ];
+        assert_eq!(state, expected.map(BabyBear::from));
+    }
+
+    #[test]
+    fn test_width_16() {
+        // The reference publishes no width-16 vector, and Plonky3's instance shares only the
+        // constants; check the reference's matrices against their dense form.
+        let diagonal = elements(&DIAGONAL_16);
+        let m4 = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+        let external = |state: [BabyBear; 16]| -> [BabyBear; 16] {
+            core::array::from_fn(|i| {
+                (0..16)
+                    .map(|j| {
+                        let entry = m4[i % 4][j % 4] * if i / 4 == j / 4 { 2 } else { 1 };
+                        BabyBear::from(entry as u32) * state[j]
+                    })
+                    .sum()
+            })
+        };
+        let sbox = |x: BabyBear| x.pow([ALPHA]);
+
+        let input: [BabyBear; 16] = core::array::from_fn(|i| BabyBear::from(i as u32 * 1000 + 7));
+        let mut expected = external(input);
+        for (r, constants) in rows::<BabyBearPrime, 16, ROUNDS_F>(&EXTERNAL_16)
+            .iter()
+            .enumerate()
+        {
+            if r == ROUNDS_F / 2 {
+                for c in INTERNAL_16 {
+                    expected[0] = sbox(expected[0] + BabyBear::from(c));
+                    let sum: BabyBear = expected.iter().sum();
+                    for (val, d) in expected.iter_mut().zip(diagonal) {
+                        *val = *val * d + sum;
+                    }
+                }
+            }
+            let state = core::array::from_fn(|i| sbox(expected[i] + constants[i]));
+            expected = external(state);
+        }
+
+        let mut state = input;
+        poseidon2_16().permute(&mut state);
+        assert_eq!(state, expected);
+    }
+
+    #[test]
+    fn test_plonky3_width_16() {
+        // Plonky3's `test_default_babybear_poseidon2_width_16`.
+        let mut state = [
+            894848333, 1437655012, 1200606629, 1690012884, 71131202, 1749206695, 1717947831,
+            120589055, 19776022, 42382981, 1831865506, 724844064, 171220207, 1299207443, 227047920,
+            1783754913,
+        ]
+        .map(BabyBear::from);
+        plonky3_16().permute(&mut state);
+        let expected: [u32; 16] = [
+            516096821, 90309867, 1101817252, 1660784290, 360715097, 1789519026, 1788910906,
+            563338433, 319524748, 1741414159, 1650859320, 894311162, 1121347488, 1692793758,
+            1052633829, 1344246938,
+        ];
+        assert_eq!(state, expected.map(BabyBear::from));
+    }
+
+    #[test]
+    fn test_plonky3_width_24() {
+        // Plonky3's `test_default_babybear_poseidon2_width_24`.
+        let mut state = [
+            886409618, 1327899896, 1902407911, 591953491, 648428576, 1844789031, 1198336108,
+            355597330, 1799586834, 59617783, 790334801, 1968791836, 559272107, 31054313,
+            1042221543, 474748436, 135686258, 263665994, 1962340735, 1741539604, 2026927696,
+            449439011, 1131357108, 50869465,
+        ]
+        .map(BabyBear::from);
+        plonky3_24().permute(&mut state);
+        let expected: [u32; 24] = [
+            882297297, 1264077610, 512812497, 782602970, 867738552, 1251075457, 309180082,
+            340784773, 524041877, 351272188, 404451680, 15001466, 322926653, 1773004150,
+            1718440818, 674682955, 1154713225, 1719133502, 324232301, 1005243141, 443371079,
+            268735940, 770060019, 718377682,
+        ];
+        assert_eq!(state, expected.map(BabyBear::from));
+    }
+
+    #[test]
+    fn test_plonky3_diagonals() {
+        #[rustfmt::skip]
+        let v16 = [
+            (-2, 0), (1, 0), (2, 0), (1, 1), (3, 0), (4, 0), (-1, 1), (-3, 0),
+            (-4, 0), (1, 8), (1, 2), (1, 3), (1, 27), (-1, 8), (-1, 4), (-1, 27),
+        ];
+        #[rustfmt::skip]
+        let v24 = [
+            (-2, 0), (1, 0), (2, 0), (1, 1), (3, 0), (4, 0), (-1, 1), (-3, 0),
+            (-4, 0), (1, 8), (1, 2), (1, 3), (1, 4), (1, 7), (1, 9), (1, 27),
+            (-1, 8), (-1, 2), (-1, 3), (-1, 4), (-1, 5), (-1, 6), (-1, 7), (-1, 27),
+        ];
+        let dyadic = |(numerator, shift)| dyadic::<BabyBearPrime>(numerator, shift);
+        assert_eq!(elements(&PLONKY3_DIAGONAL_16), v16.map(dyadic));
+        assert_eq!(elements(&PLONKY3_DIAGONAL_24), v24.map(dyadic));
+    }
+}
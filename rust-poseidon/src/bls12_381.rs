@@ -0,0 +1,166 @@
+//! Poseidon over the BLS12-381 scalar field.
+//!
+//! The instances are the reference implementation's for this field, with `x^5`, 8 full
+//! rounds and the partial rounds its round-number script gives for 128-bit security: 56
+//! for widths 2, 3 and 4, and 57 for width 8. Their constants are generated on first use
+//! and match, entry for entry, the tables HorizenLabs' zkhash publishes for this field.
+
+use ark_ff::fields::{Fp256, MontBackend, MontConfig};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "std")]
+use crate::{
+    generate::{cached, OptimizedConstants, ReferenceParameters, SBOX_ZKHASH},
+    permutation::PoseidonParameters,
+};
+
+#[derive(MontConfig)]
+#[modulus = "52435875175126190479447740508185965837690552500527637822603658699938581184513"]
+#[generator = "7"]
+pub struct FrConfig;
+pub type Fr = Fp256<MontBackend<FrConfig, 4>>;
+
+pub const ALPHA: u64 = 5;
+pub const ROUNDS_F: usize = 8;
+
+macro_rules! instance {
+    ($name:ident, $t:literal, $rounds_p:literal) => {
+        #[doc = concat!("The width-", $t, " instance, with ", $rounds_p, " partial rounds.")]
+        #[cfg(feature = "std")]
+        pub fn $name() -> PoseidonParameters<'static, Fr, $t> {
+            static CONSTANTS: OnceLock<OptimizedConstants<Fr, $t>> = OnceLock::new();
+            cached(&CONSTANTS, || {
+                ReferenceParameters::generate(SBOX_ZKHASH, ALPHA, ROUNDS_F, $rounds_p)
+            })
+        }
+    };
+}
+
+instance!(poseidon_2, 2, 56);
+instance!(poseidon_3, 3, 56);
+instance!(poseidon_4, 4, 56);
+instance!(poseidon_8, 8, 57);
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{generate::hex, permutation::Permutation};
+
+    /// zkhash's `poseidon_instance_bls12.rs`, extracted verbatim.
+    const ZKHASH_TABLES: &str = include_str!("../vectors/bls12_381_zkhash.json");
+
+    fn permute<P: Permutation<T, Field = Fr>, const T: usize>(instance: P) -> [Fr; T] {
+        let mut state = core::array::from_fn(|i| Fr::from(i as u64));
+        instance.permute(&mut state);
+        state
+    }
+
+    fn table<const T: usize>(rows: &Value) -> Vec<[Fr; T]> {
+        rows.as_array()
+            .unwrap()
+            .iter()
+            .map(|row| {
+                let row = row.as_array().unwrap();
+                assert_eq!(row.len(), T);
+                core::array::from_fn(|i| hex(row[i].as_str().unwrap()))
+            })
+            .collect()
+    }
+
+    fn assert_zkhash_tables<const T: usize>(rounds_p: usize) {
+        let tables: Value = serde_json::from_str(ZKHASH_TABLES).unwrap();
+        let instance = tables["instances"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|instance| instance["width"] == T)
+            .unwrap();
+        assert_eq!(instance["rounds_f"], ROUNDS_F);
+        assert_eq!(instance["rounds_p"], rounds_p);
+
+        let reference =
+            ReferenceParameters::<Fr, T>::generate(SBOX_ZKHASH, ALPHA, ROUNDS_F, rounds_p);
+        assert_eq!(reference.mds.to_vec(), table::<T>(&instance["mds"]));
+        assert_eq!(
+            reference.round_constants,
+            table::<T>(&instance["round_constants"])
+        );
+    }
+
+    #[test]
+    fn test_reference_tables() {
+        assert_zkhash_tables::<2>(56);
+        assert_zkhash_tables::<3>(56);
+        assert_zkhash_tables::<4>(56);
+        assert_zkhash_tables::<8>(57);
+    }
+
+    #[test]
+    fn test_optimized_matches_reference() {
+        fn check<const T: usize>(instance: PoseidonParameters<'static, Fr, T>) {
+            let reference = ReferenceParameters::<Fr, T>::generate(
+                SBOX_ZKHASH,
+                ALPHA,
+                ROUNDS_F,
+                instance.rounds_p,
+            );
+            assert!(reference.agrees_with(&instance));
+        }
+        check(poseidon_2());
+        check(poseidon_3());
+        check(poseidon_4());
+        check(poseidon_8());
+    }
+
+    #[test]
+    fn test_known_answers() {
+        // zkhash's `kats` test vectors: the permutation of [0, 1, ..., T - 1].
+        assert_eq!(
+            permute(poseidon_2()),
+            [
+                hex("0x1dc37ce34aeee058292bb73bff9acffce73a8a92f3d6d1daa8b77d9516b5c837"),
+                hex("0x534cc8001b9c21da25d62749e136ea3d702651ba129f0d5ed7847cf81bc8b042"),
+            ]
+        );
+        assert_eq!(
+            permute(poseidon_3()),
+            [
+                hex("0x200e6982ac00df8fa65cef1fde9f21373fdbbfd98f2df1eb5fa04f3302ab0397"),
+                hex("0x2233c9a40d91c1f643b700f836a1ac231c3f3a8d438ad1609355e1b7317a47e5"),
+                hex("0x2eae6736db3c086ad29938869dedbf969dd9804a58aa228ec467b7d5a08dc765"),
+            ]
+        );
+    }
+
+    /// A snapshot, not a conformance test: zkhash publishes no vectors for widths 4 and 8,
+    /// so these outputs were recorded from this crate. Conformance at these widths rests on
+    /// `test_reference_tables`; this only catches a change in how the tables are used.
+    #[test]
+    fn test_snapshot_widths_4_and_8() {
+        assert_eq!(
+            permute(poseidon_4()),
+            [
+                hex("0x3ee96c25cccd2d4ce9423040834a34e61fccbd98d1820ee60d31098867511dc3"),
+                hex("0x199425b0c3d883fee473e656c2fd7fcb1505acae38882cc9f700af90c419f631"),
+                hex("0x4715733d30978870e3b199c42e836f60b05654bee15e1b7b2727128477e8671f"),
+                hex("0x3dcb25fd97232b62619a0eed46588a42f9860cb096789b5263faf3cc1c0cd5fc"),
+            ]
+        );
+        assert_eq!(
+            permute(poseidon_8()),
+            [
+                hex("0x13f2636c78f8e8d59aa615fa669b68d23dc89d47b26f06052afcbc1c0933ffbe"),
+                hex("0x0d953cbd9befc82614fe8e2c1edbb1b50919ef2eb6bfaefbbe737039e3c5f8a2"),
+                hex("0x37db098a67bcbc4b94b62106af44b1b3e9b6d4a5d7dc14fd9dcf0885a0f36888"),
+                hex("0x4f48e101ecab61473889ec20261383943034e2a328ba1cd60feb44bec58d151c"),
+                hex("0x623c26576cf3b33011ea22b722d7baa173b8efb18c26c54f00c4a413d0b5af7a"),
+                hex("0x26b7561edf21d399b7e664ee561c9f5daa918d0d7f19bf8e194fcabf349d1f92"),
+                hex("0x03b543d81976eef5c8b1cd462eb30a6c916c668e2db4cfa1a4ce248990a88d13"),
+                hex("0x2a5606c6f8784f4afa74a81f1e956315c10a0ca1ab3dd8301740f1dda1040ea9"),
+            ]
+        );
+    }
+}
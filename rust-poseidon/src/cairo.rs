@@ -0,0 +1,422 @@
+use std::fmt;
+
+use ark_ff::{AdditiveGroup, Field, PrimeField};
+
+use crate::{
+    constants::CircomPoseidonConstants,
+    field::Fr,
+    functional::poseidon_hash,
+    u384::{U384Error, U384},
+};
+
+pub const CONSTANT_TABLE_NAME: &str = "POSEIDON_GRUMPKIN_GRUMPKIN_CONSTANTS";
+
+/// Where a constant of the garaga circuit comes from in `CircomPoseidonConstants`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstantRole {
+    /// The initial capacity element of the state.
+    Zero,
+    C(usize),
+    M(usize, usize),
+    P(usize, usize),
+    S(usize),
+}
+
+impl fmt::Display for ConstantRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstantRole::Zero => write!(f, "zero"),
+            ConstantRole::C(i) => write!(f, "C[{i}]"),
+            ConstantRole::M(i, j) => write!(f, "M[{i}][{j}]"),
+            ConstantRole::P(i, j) => write!(f, "P[{i}][{j}]"),
+            ConstantRole::S(i) => write!(f, "S[{i}]"),
+        }
+    }
+}
+
+/// One entry of the constant table, with every role that reuses its value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstantEntry {
+    pub value: Fr,
+    pub roles: Vec<ConstantRole>,
+}
+
+/// The constant table in the order garaga's `set_or_get_constant` emits it.
+///
+/// `circuits/poseidon.py` registers the zero state element, then `C`, `M`, `P` and `S`
+/// row by row; a value already registered is reused instead of getting a new slot.
+pub fn expected_constant_table(constants: &CircomPoseidonConstants) -> Vec<ConstantEntry> {
+    let roles = std::iter::once((ConstantRole::Zero, Fr::ZERO))
+        .chain(
+            constants
+                .c
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (ConstantRole::C(i), *v)),
+        )
+        .chain(constants.m.iter().enumerate().flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(j, v)| (ConstantRole::M(i, j), *v))
+        }))
+        .chain(constants.p.iter().enumerate().flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(j, v)| (ConstantRole::P(i, j), *v))
+        }))
+        .chain(
+            constants
+                .s
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (ConstantRole::S(i), *v)),
+        );
+
+    roles.fold(
+        Vec::new(),
+        |mut table: Vec<ConstantEntry>, (role, value)| {
+            match table.iter_mut().find(|entry| entry.value == value) {
+                Some(entry) => entry.roles.push(role),
+                None => table.push(ConstantEntry {
+                    value,
+                    roles: vec![role],
+                }),
+            }
+            table
+        },
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CairoError {
+    MissingTable,
+    Literal { index: usize, error: U384Error },
+    DeclaredLength { declared: usize, found: usize },
+    Mismatch(Vec<TableMismatch>),
+}
+
+impl fmt::Display for CairoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CairoError::MissingTable => {
+                write!(f, "no `{CONSTANT_TABLE_NAME}: [u384; N]` array found")
+            }
+            CairoError::Literal { index, error } => write!(f, "entry {index}: {error}"),
+            CairoError::DeclaredLength { declared, found } => write!(
+                f,
+                "array is declared with {declared} entries but contains {found}"
+            ),
+            CairoError::Mismatch(mismatches) => {
+                write!(
+                    f,
+                    "{} entries disagree with the Rust constants",
+                    mismatches.len()
+                )?;
+                mismatches
+                    .iter()
+                    .try_for_each(|mismatch| write!(f, "\n  {mismatch}"))
+            }
+        }
+    }
+}
+
+impl std::error::Error for CairoError {}
+
+/// Extracts the `u384` entries of the constant table array from a Cairo source file.
+pub fn parse_constant_table(source: &str) -> Result<Vec<U384>, CairoError> {
+    let declaration = source
+        .find(&format!("{CONSTANT_TABLE_NAME}:"))
+        .map(|start| &source[start + CONSTANT_TABLE_NAME.len() + 1..])
+        .ok_or(CairoError::MissingTable)?;
+    let (declared, body) = declaration
+        .trim_start()
+        .strip_prefix("[u384;")
+        .and_then(|rest| rest.split_once(']'))
+        .and_then(|(length, rest)| {
+            let body = rest.trim_start().strip_prefix('=')?.trim_start();
+            Some((
+                length.trim().parse::<usize>().ok()?,
+                body.strip_prefix('[')?,
+            ))
+        })
+        .ok_or(CairoError::MissingTable)?;
+    let body = &body[..body.find("];").ok_or(CairoError::MissingTable)?];
+
+    let entries = body
+        .split_inclusive('}')
+        .map(str::trim)
+        .map(|entry| entry.strip_prefix(',').unwrap_or(entry).trim())
+        .filter(|entry| !entry.is_empty())
+        .enumerate()
+        .map(|(index, entry)| {
+            entry
+                .parse::<U384>()
+                .map_err(|error| CairoError::Literal { index, error })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if entries.len() != declared {
+        return Err(CairoError::DeclaredLength {
+            declared,
+            found: entries.len(),
+        });
+    }
+    Ok(entries)
+}
+
+/// A single disagreement between the Cairo table and `CircomPoseidonConstants`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableMismatch {
+    pub index: usize,
+    pub roles: Vec<ConstantRole>,
+    pub expected: Option<U384>,
+    pub found: Option<U384>,
+}
+
+impl fmt::Display for TableMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let roles = self
+            .roles
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "entry {}", self.index)?;
+        if !roles.is_empty() {
+            write!(f, " ({roles})")?;
+        }
+        match (&self.expected, &self.found) {
+            (Some(expected), Some(found)) => {
+                write!(f, ": expected {expected}, found {found}")
+            }
+            (Some(expected), None) => write!(f, ": expected {expected}, missing"),
+            (None, Some(found)) => write!(f, ": unexpected extra entry {found}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Compares the Cairo table against the Rust constants, entry by entry.
+pub fn check_constant_table(
+    source: &str,
+    constants: &CircomPoseidonConstants,
+) -> Result<(), CairoError> {
+    let found = parse_constant_table(source)?;
+    let expected = expected_constant_table(constants);
+
+    let mismatches: Vec<_> = (0..expected.len().max(found.len()))
+        .filter_map(|index| {
+            let entry = expected.get(index);
+            let expected = entry.map(|entry| U384::from(entry.value));
+            let found = found.get(index).copied();
+            (expected != found).then(|| TableMismatch {
+                index,
+                roles: entry.map(|entry| entry.roles.clone()).unwrap_or_default(),
+                expected,
+                found,
+            })
+        })
+        .collect();
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(CairoError::Mismatch(mismatches))
+    }
+}
+
+pub const GENERATED_TESTS_SEED: u64 = 0x706f736569646f6e;
+pub const GENERATED_TESTS_RANDOM_CASES: usize = 32;
+
+/// Inputs chosen to sit on the edges of the field and of the 96-bit limbs.
+pub fn edge_case_inputs() -> Vec<Fr> {
+    let power = |bits: u32| Fr::from(2).pow([bits as u64]);
+    vec![
+        Fr::ZERO,
+        Fr::ONE,
+        Fr::from(2),
+        power(96) - Fr::ONE,
+        power(96),
+        power(192) - Fr::ONE,
+        power(192),
+        power(253),
+        -Fr::from(2),
+        -Fr::ONE,
+    ]
+}
+
+/// Renders a Cairo test module checking `run_poseidon_grumpkin_circuit` against
+/// `poseidon_hash` on every edge case pair and on `random_cases` pseudo-random pairs.
+pub fn generate_test_module(random_cases: usize, seed: u64) -> String {
+    let edges = edge_case_inputs();
+    let edge_pairs = edges
+        .iter()
+        .flat_map(|x| [(*x, Fr::ZERO), (Fr::ZERO, *x), (*x, *x), (*x, -Fr::ONE)])
+        .fold(Vec::new(), |mut pairs, pair| {
+            if !pairs.contains(&pair) {
+                pairs.push(pair);
+            }
+            pairs
+        });
+
+    let mut rng = SplitMix64(seed);
+    let random_pairs = (0..random_cases).map(|_| (rng.next_fr(), rng.next_fr()));
+
+    let cases = edge_pairs
+        .into_iter()
+        .enumerate()
+        .map(|(i, pair)| (format!("edge_{i}"), pair))
+        .chain(
+            random_pairs
+                .enumerate()
+                .map(|(i, pair)| (format!("random_{i}"), pair)),
+        );
+
+    let mut module = format!(
+        "// Generated by `cargo run -- cairo-tests {random_cases} {seed}` in rust-poseidon; do not edit.\n\
+         use core::circuit::u384;\n\
+         use super::run_poseidon_grumpkin_circuit;\n"
+    );
+    for (name, (x, y)) in cases {
+        let z = poseidon_hash(x, y);
+        module.push_str(&format!(
+            "\n#[test]\nfn test_{name}() {{\n{}{}{}    assert(run_poseidon_grumpkin_circuit(x, y) == z, '{name}');\n}}\n",
+            cairo_let("x", x),
+            cairo_let("y", y),
+            cairo_let("z", z),
+        ));
+    }
+    module
+}
+
+fn cairo_let(name: &str, value: Fr) -> String {
+    let value = U384::from(value);
+    let line = format!("    let {name}: u384 = {};\n", value.to_decimal_literal());
+    if line.len() <= 101 {
+        return line;
+    }
+    format!(
+        "    let {name}: u384 = u384 {{\n        limb0: {},\n        limb1: {},\n        limb2: {},\n        limb3: {},\n    }};\n",
+        value.limb0, value.limb1, value.limb2, value.limb3
+    )
+}
+
+/// Small, stable generator so regenerated test modules stay byte-for-byte identical.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let z = (self.0 ^ (self.0 >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        let z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_fr(&mut self) -> Fr {
+        let bytes: Vec<u8> = (0..4).flat_map(|_| self.next_u64().to_le_bytes()).collect();
+        Fr::from_le_bytes_mod_order(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB_CAIRO: &str = include_str!("../../poseidon/src/lib.cairo");
+    const GENERATED_TESTS_CAIRO: &str = include_str!("../../poseidon/src/generated_tests.cairo");
+
+    #[test]
+    fn test_cairo_constant_table_roles() {
+        let table = expected_constant_table(&CircomPoseidonConstants::default());
+
+        assert_eq!(table.len(), 323);
+        assert_eq!(table[0].roles, vec![ConstantRole::Zero]);
+        assert_eq!(table[1].roles, vec![ConstantRole::C(0)]);
+        assert_eq!(table[82].roles[0], ConstantRole::M(0, 0));
+        assert!(table[82].roles.contains(&ConstantRole::S(0)));
+    }
+
+    #[test]
+    fn test_cairo_constant_table_matches() {
+        let result = check_constant_table(LIB_CAIRO, &CircomPoseidonConstants::default());
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn test_cairo_constant_table_mismatch() {
+        let tampered = LIB_CAIRO.replacen(
+            "limb0: 0xd00868df5696fff40956e864",
+            "limb0: 0xd00868df5696fff40956e865",
+            1,
+        );
+
+        let Err(CairoError::Mismatch(mismatches)) =
+            check_constant_table(&tampered, &CircomPoseidonConstants::default())
+        else {
+            panic!("tampered table was accepted");
+        };
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].index, 2);
+        assert_eq!(mismatches[0].roles, vec![ConstantRole::C(1)]);
+        assert_eq!(
+            mismatches[0].found.unwrap().limb0,
+            0xd00868df5696fff40956e865
+        );
+        assert!(mismatches[0]
+            .to_string()
+            .starts_with("entry 2 (C[1]): expected"));
+    }
+
+    #[test]
+    fn test_cairo_constant_table_truncated() {
+        let truncated = LIB_CAIRO.replacen("[u384; 323]", "[u384; 324]", 1);
+        assert_eq!(
+            parse_constant_table(&truncated),
+            Err(CairoError::DeclaredLength {
+                declared: 324,
+                found: 323
+            })
+        );
+        assert_eq!(parse_constant_table(""), Err(CairoError::MissingTable));
+    }
+
+    #[test]
+    fn test_cairo_generated_tests_are_up_to_date() {
+        let generated = generate_test_module(GENERATED_TESTS_RANDOM_CASES, GENERATED_TESTS_SEED);
+        assert!(
+            generated == GENERATED_TESTS_CAIRO,
+            "poseidon/src/generated_tests.cairo is stale; regenerate it with `cargo run -- cairo-tests`"
+        );
+    }
+
+    #[test]
+    fn test_cairo_generated_tests_include_known_answers() {
+        let generated = generate_test_module(0, GENERATED_TESTS_SEED);
+        let expected = U384::from(poseidon_hash(Fr::ONE, Fr::from(2)));
+        assert_eq!(
+            expected.to_decimal_literal(),
+            "u384 { limb0: 35961034779122590217458358426, limb1: 19176342235218042678530152434, limb2: 1251086958891274305, limb3: 0 }"
+        );
+        assert!(generated.contains("fn test_edge_0()"));
+        assert!(!generated.contains("fn test_random_0()"));
+    }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn parse_constant_table_never_panics(
+                body in "(u384 \\{ limb0: [0-9a-fx]{1,6}, limb1: 0, limb2: 0, limb3: 0 \\},?\\s?){0,4}|\\PC{0,40}",
+                declared in 0..6usize,
+            ) {
+                let source = format!("const {CONSTANT_TABLE_NAME}: [u384; {declared}] = [{body}];");
+                if let Ok(entries) = parse_constant_table(&source) {
+                    prop_assert_eq!(entries.len(), declared);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! circomlib's Poseidon at every width it has, as circomlibjs' `poseidon` computes it.
+//!
+//! circomlib hashes 1 to 16 inputs with a state of one more element: `x^5`, 8 full rounds
+//! and the partial rounds of `ROUNDS_P`. Width 3 is `CircomPoseidonConstants`. The other
+//! widths are generated on first use by the reference's Grain procedure, which gives
+//! circomlib's width-3 tables exactly, and their tables match the ones light-poseidon
+//! publishes for circom at widths 2 to 13.
+
+use std::sync::OnceLock;
+
+use crate::{
+    constants::CircomPoseidonConstants,
+    field::Fr,
+    generate::{cached, OptimizedConstants, ReferenceParameters, SBOX_REFERENCE},
+    permutation::{Permutation, PoseidonParameters},
+};
+
+pub const ALPHA: u64 = 5;
+pub const ROUNDS_F: usize = 8;
+/// The partial rounds for widths 2 to 17, circomlibjs' `N_ROUNDS_P`.
+pub const ROUNDS_P: [usize; MAX_INPUTS] = [
+    56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68,
+];
+/// The most inputs one hash takes.
+pub const MAX_INPUTS: usize = 16;
+
+macro_rules! instances {
+    ($(($name:ident, $t:literal)),* $(,)?) => {
+        $(
+            #[doc = concat!("The width-", $t, " instance.")]
+            pub fn $name() -> PoseidonParameters<'static, Fr, $t> {
+                static CONSTANTS: OnceLock<OptimizedConstants<Fr, $t>> = OnceLock::new();
+                cached(&CONSTANTS, || {
+                    ReferenceParameters::generate(SBOX_REFERENCE, ALPHA, ROUNDS_F, ROUNDS_P[$t - 2])
+                })
+            }
+        )*
+
+        /// The circomlib permutation of `state`, at its width. `None` unless the width is
+        /// between 2 and 17.
+        pub fn permute(state: &[Fr]) -> Option<Vec<Fr>> {
+            match state.len() {
+                3 => Some(permute_with(CircomPoseidonConstants::shared(), state)),
+                $($t => Some(permute_with($name(), state)),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+instances!(
+    (poseidon_2, 2),
+    (poseidon_4, 4),
+    (poseidon_5, 5),
+    (poseidon_6, 6),
+    (poseidon_7, 7),
+    (poseidon_8, 8),
+    (poseidon_9, 9),
+    (poseidon_10, 10),
+    (poseidon_11, 11),
+    (poseidon_12, 12),
+    (poseidon_13, 13),
+    (poseidon_14, 14),
+    (poseidon_15, 15),
+    (poseidon_16, 16),
+    (poseidon_17, 17),
+);
+
+fn permute_with<const T: usize>(
+    instance: impl Permutation<T, Field = Fr>,
+    state: &[Fr],
+) -> Vec<Fr> {
+    let mut state: [Fr; T] = state.try_into().expect("dispatched on the width");
+    instance.permute(&mut state);
+    state.to_vec()
+}
+
+/// circomlibjs' `poseidon(inputs, initState)`: the first element of the permuted
+/// `[init_state, inputs...]`. `None` unless there are 1 to `MAX_INPUTS` inputs.
+pub fn hash(inputs: &[Fr], init_state: Fr) -> Option<Fr> {
+    let state: Vec<Fr> = core::iter::once(init_state)
+        .chain(inputs.iter().copied())
+        .collect();
+    permute(&state).map(|state| state[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{functional::poseidon_hash_with_initial_state, generate::hex};
+
+    fn hash_decimal(inputs: &[u64]) -> String {
+        let inputs: Vec<Fr> = inputs.iter().map(|&x| Fr::from(x)).collect();
+        hash(&inputs, Fr::from(0)).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_circomlibjs_vectors() {
+        // circomlibjs' poseidon test vectors.
+        let vectors: [(&[u64], &str); 6] = [
+            (
+                &[1, 2],
+                "7853200120776062878684798364095072458815029376092732009249414926327459813530",
+            ),
+            (
+                &[1, 2, 3, 4],
+                "18821383157269793795438455681495246036402687001665670618754263018637548127333",
+            ),
+            (
+                &[1, 2, 0, 0, 0],
+                "1018317224307729531995786483840663576608797660851238720571059489595066344487",
+            ),
+            (
+                &[3, 4, 0, 0, 0],
+                "5811595552068139067952687508729883632420015185677766880877743348592482390548",
+            ),
+            (
+                &[1, 2, 0, 0, 0, 0],
+                "15336558801450556532856248569924170992202208561737609669134139141992924267169",
+            ),
+            (
+                &[3, 4, 0, 0, 0, 0],
+                "12263118664590987767234828103155242843640892839966517009184493198782366909018",
+            ),
+        ];
+        for (inputs, expected) in vectors {
+            assert_eq!(hash_decimal(inputs), expected, "{inputs:?}");
+        }
+        assert_eq!(
+            hash_decimal(&(1..=16).collect::<Vec<_>>()),
+            "9989051620750914585850546081941653841776809718687451684622678807385399211877"
+        );
+    }
+
+    #[test]
+    fn test_light_poseidon_tables() {
+        // The first round constant and MDS entry of light-poseidon's `bn254_x5.rs` at
+        // each width it has.
+        fn check<const T: usize>(round_constant: &str, mds: &str) {
+            let reference = ReferenceParameters::<Fr, T>::generate(
+                SBOX_REFERENCE,
+                ALPHA,
+                ROUNDS_F,
+                ROUNDS_P[T - 2],
+            );
+            assert_eq!(
+                reference.round_constants[0][0],
+                hex(round_constant),
+                "width {T}"
+            );
+            assert_eq!(reference.mds[0][0], hex(mds), "width {T}");
+        }
+        check::<2>(
+            "0x09c46e9ec68e9bd4fe1faaba294cba38a71aa177534cdd1b6c7dc0dbd0abd7a7",
+            "0x066f6f85d6f68a85ec10345351a23a3aaf07f38af8c952a7bceca70bd2af7ad5",
+        );
+        check::<3>(
+            "0x0ee9a592ba9a9518d05986d656f40c2114c4993c11bb29938d21d47304cd8e6e",
+            "0x109b7f411ba0e4c9b2b70caf5c36a7b194be7c11ad24378bfedb68592ba8118b",
+        );
+        check::<4>(
+            "0x19b849f69450b06848da1d39bd5e4a4302bb86744edc26238b0878e269ed23e5",
+            "0x236d13393ef85cc48a351dd786dd7a1de5e39942296127fd87947223ae5108ad",
+        );
+        check::<5>(
+            "0x0eb544fee2815dda7f53e29ccac98ed7d889bb4ebd47c3864f3c2bd81a6da891",
+            "0x251e7fdf99591080080b0af133b9e4369f22e57ace3cd7f64fc6fdbcf38d7da1",
+        );
+        check::<6>(
+            "0x1448614598e00f98e7ae7dea45fbd83bd968653ef8390cde2e86b706ad40c651",
+            "0x124666f80561ed5916f2f070b1bd248c6d53f44d273d956a0c87b917692a4d18",
+        );
+        check::<7>(
+            "0x2197703fceb4cbf07c6dbf46c4ad93e7d14e554db66d09102ff84824743fe4e7",
+            "0x2abd9e16b7b48289aa19cdfec726fa3fa15cc1625102ae511bca45f39d337e9d",
+        );
+        check::<8>(
+            "0x123992df3b9daa65139ec13fbb52f7d348e134333684c1596feb0e8d8c3ad596",
+            "0x1aa4d470abeaa2357079129d114bab1170944f929fc178216ad847d4430f3f27",
+        );
+        check::<9>(
+            "0x2088ce9534577bf38be7bc457f2756d558d66e0c07b9cc001a580bd42cda0e77",
+            "0x0190f922d97c8a7dcf0a142a3be27749d1c64bc22f1c556aaa24925d158cac56",
+        );
+        check::<10>(
+            "0x0e1962c232fd0a6bb54ad8962a82b9838cfef19d290a55fc49d6debd061cd2f6",
+            "0x0b1e797eee3f15b88ce3a1fafec88da1d6329ac3c34712db570de886cf569098",
+        );
+        check::<11>(
+            "0x0752af3c6fdccaf3868276685f0a69b9749e1706a82917b64ec2ef847f804559",
+            "0x026dba58ade8cda188f49e52fce7167b8f219dbd924d7786b0f98fb799fefa2e",
+        );
+        check::<12>(
+            "0x1512df0135b6692589f071140a60749cf775c642b300da2fb4ad5c6e23ad4e5f",
+            "0x2cb13179d6e8a4ad1b9fec37376253cf64386ad905e240575fca86539b272dc6",
+        );
+        check::<13>(
+            "0x1373c771cdf15121a224f330d84b6688ef9fe0038a3bc26a28e3196578a0000d",
+            "0x0d064dee824928d39f2a8be7d2fa583223c33995784d228839adde2559592592",
+        );
+    }
+
+    #[test]
+    fn test_width_3_is_circomlib() {
+        let [x, y, init] = ["1", "2", "3"].map(|s| Fr::from_str(s).unwrap());
+        assert_eq!(
+            hash(&[x, y], init),
+            Some(poseidon_hash_with_initial_state(init, x, y))
+        );
+    }
+
+    #[test]
+    fn test_permute_widths() {
+        for t in 2..=MAX_INPUTS + 1 {
+            let state: Vec<Fr> = (0..t as u64).map(Fr::from).collect();
+            let permuted = permute(&state).unwrap();
+            assert_eq!(permuted.len(), t);
+            assert_ne!(permuted, state);
+        }
+        assert_eq!(permute(&[Fr::from(1)]), None);
+        assert_eq!(permute(&[Fr::from(1); MAX_INPUTS + 2]), None);
+        assert_eq!(hash(&[], Fr::from(0)), None);
+    }
+}
@@ -0,0 +1,650 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+};
+
+use ark_ff::{AdditiveGroup, PrimeField};
+
+use crate::{
+    constants::{CircomPoseidonConstants, N_ROUNDS_F, N_ROUNDS_P, T},
+    field::Fr,
+    u384::U384,
+};
+
+pub type NodeId = usize;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node {
+    Input(String),
+    Constant(Fr),
+    Add(NodeId, NodeId),
+    Mul(NodeId, NodeId),
+}
+
+/// A DAG of field additions and multiplications, in topological order.
+#[derive(Clone, Debug, Default)]
+pub struct Circuit {
+    nodes: Vec<Node>,
+    /// The node of each registered constant.
+    constants: HashMap<Fr, NodeId>,
+    outputs: Vec<(String, NodeId)>,
+}
+
+impl Circuit {
+    pub fn input(&mut self, name: &str) -> NodeId {
+        self.push(Node::Input(name.to_string()))
+    }
+
+    /// Registers a constant, reusing the existing node when the value is already known.
+    pub fn constant(&mut self, value: Fr) -> NodeId {
+        if let Some(&node) = self.constants.get(&value) {
+            return node;
+        }
+        let node = self.push(Node::Constant(value));
+        self.constants.insert(value, node);
+        node
+    }
+
+    pub fn add(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.push(Node::Add(a, b))
+    }
+
+    pub fn mul(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.push(Node::Mul(a, b))
+    }
+
+    pub fn output(&mut self, name: &str, node: NodeId) {
+        self.outputs.push((name.to_string(), node));
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn outputs(&self) -> &[(String, NodeId)] {
+        &self.outputs
+    }
+
+    /// Evaluates the circuit natively, taking inputs in declaration order.
+    pub fn evaluate(&self, inputs: &[Fr]) -> Vec<Fr> {
+        let mut inputs = inputs.iter();
+        let values = self.nodes.iter().fold(Vec::new(), |mut values, node| {
+            let value = match node {
+                Node::Input(name) => *inputs
+                    .next()
+                    .unwrap_or_else(|| panic!("missing value for input `{name}`")),
+                Node::Constant(value) => *value,
+                Node::Add(a, b) => values[*a] + values[*b],
+                Node::Mul(a, b) => values[*a] * values[*b],
+            };
+            values.push(value);
+            values
+        });
+        self.outputs.iter().map(|(_, node)| values[*node]).collect()
+    }
+
+    pub fn stats(&self) -> CircuitStats {
+        self.nodes
+            .iter()
+            .fold(CircuitStats::default(), |mut stats, node| {
+                match node {
+                    Node::Input(_) => stats.inputs += 1,
+                    Node::Constant(_) => stats.constants += 1,
+                    Node::Add(..) => stats.adds += 1,
+                    Node::Mul(..) => stats.muls += 1,
+                }
+                stats
+            })
+    }
+
+    fn push(&mut self, node: Node) -> NodeId {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// Nodes whose value does not depend on any input.
+    fn constant_nodes(&self) -> Vec<bool> {
+        self.nodes.iter().fold(Vec::new(), |mut constant, node| {
+            let is_constant = match node {
+                Node::Input(_) => false,
+                Node::Constant(_) => true,
+                Node::Add(a, b) | Node::Mul(a, b) => constant[*a] && constant[*b],
+            };
+            constant.push(is_constant);
+            constant
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub inputs: usize,
+    pub constants: usize,
+    pub adds: usize,
+    pub muls: usize,
+}
+
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes ({} inputs, {} constants, {} adds, {} muls)",
+            self.inputs + self.constants + self.adds + self.muls,
+            self.inputs,
+            self.constants,
+            self.adds,
+            self.muls
+        )
+    }
+}
+
+/// Builds the circomlib `Poseidon(2)` hash the way `circuits/poseidon.py` feeds it to garaga.
+pub fn poseidon_circuit(constants: &CircomPoseidonConstants) -> Circuit {
+    let mut circuit = Circuit::default();
+
+    let zero = circuit.constant(Fr::ZERO);
+    let x = circuit.input("x");
+    let y = circuit.input("y");
+
+    let c: Vec<_> = constants.c.iter().map(|v| circuit.constant(*v)).collect();
+    let m: Vec<Vec<_>> = constants
+        .m
+        .iter()
+        .map(|row| row.iter().map(|v| circuit.constant(*v)).collect())
+        .collect();
+    let p: Vec<Vec<_>> = constants
+        .p
+        .iter()
+        .map(|row| row.iter().map(|v| circuit.constant(*v)).collect())
+        .collect();
+    let s: Vec<_> = constants.s.iter().map(|v| circuit.constant(*v)).collect();
+
+    let sigma = |circuit: &mut Circuit, value| {
+        let x2 = circuit.mul(value, value);
+        let x4 = circuit.mul(x2, x2);
+        circuit.mul(x4, value)
+    };
+    let ark = |circuit: &mut Circuit, r: usize, state: Vec<NodeId>| -> Vec<NodeId> {
+        (0..T).map(|i| circuit.add(state[i], c[i + r])).collect()
+    };
+    let iterated_add = |circuit: &mut Circuit, terms: Vec<NodeId>| {
+        terms[1..]
+            .iter()
+            .fold(terms[0], |acc, term| circuit.add(acc, *term))
+    };
+    let mix = |circuit: &mut Circuit, matrix: &[Vec<NodeId>], state: Vec<NodeId>| {
+        (0..T)
+            .map(|i| {
+                let terms = (0..T)
+                    .map(|j| circuit.mul(matrix[j][i], state[j]))
+                    .collect();
+                iterated_add(circuit, terms)
+            })
+            .collect::<Vec<_>>()
+    };
+    let mix_s = |circuit: &mut Circuit, r: usize, state: Vec<NodeId>| {
+        let terms = (0..T)
+            .map(|i| circuit.mul(s[(T * 2 - 1) * r + i], state[i]))
+            .collect();
+        let first = iterated_add(circuit, terms);
+        std::iter::once(first)
+            .chain((1..T).map(|i| {
+                let product = circuit.mul(state[0], s[(T * 2 - 1) * r + T + i - 1]);
+                circuit.add(state[i], product)
+            }))
+            .collect::<Vec<_>>()
+    };
+    let full_round =
+        |circuit: &mut Circuit, r: usize, matrix: &[Vec<NodeId>], state: Vec<NodeId>| {
+            let state = state.into_iter().map(|v| sigma(circuit, v)).collect();
+            let state = ark(circuit, r, state);
+            mix(circuit, matrix, state)
+        };
+
+    let mut state = ark(&mut circuit, 0, vec![zero, x, y]);
+    for r in 0..N_ROUNDS_F / 2 - 1 {
+        state = full_round(&mut circuit, (r + 1) * T, &m, state);
+    }
+    state = full_round(&mut circuit, N_ROUNDS_F / 2 * T, &p, state);
+    for r in 0..N_ROUNDS_P {
+        state[0] = sigma(&mut circuit, state[0]);
+        state[0] = circuit.add(state[0], c[(N_ROUNDS_F / 2 + 1) * T + r]);
+        state = mix_s(&mut circuit, r, state);
+    }
+    for r in 0..N_ROUNDS_F / 2 - 1 {
+        state = full_round(
+            &mut circuit,
+            (N_ROUNDS_F / 2 + 1) * T + N_ROUNDS_P + r * T,
+            &m,
+            state,
+        );
+    }
+    let state: Vec<_> = state.into_iter().map(|v| sigma(&mut circuit, v)).collect();
+    let terms = (0..T).map(|j| circuit.mul(m[j][0], state[j])).collect();
+    let z = iterated_add(&mut circuit, terms);
+    circuit.output("z", z);
+
+    circuit
+}
+
+/// A target language the circuit can be emitted to.
+pub trait Backend {
+    fn name(&self) -> &'static str;
+
+    /// Number of gates (or constraints) the emitted code evaluates.
+    fn gate_count(&self, circuit: &Circuit) -> usize;
+
+    fn emit(&self, circuit: &Circuit) -> String;
+}
+
+/// Cairo code in the shape garaga's `ModuloCircuit.compile_circuit` produces.
+pub struct CairoBackend {
+    pub name: String,
+    pub curve: String,
+}
+
+impl Default for CairoBackend {
+    fn default() -> Self {
+        CairoBackend {
+            name: "poseidon_grumpkin".to_string(),
+            curve: "GRUMPKIN".to_string(),
+        }
+    }
+}
+
+impl Backend for CairoBackend {
+    fn name(&self) -> &'static str {
+        "cairo"
+    }
+
+    fn gate_count(&self, circuit: &Circuit) -> usize {
+        let stats = circuit.stats();
+        stats.adds + stats.muls
+    }
+
+    fn emit(&self, circuit: &Circuit) -> String {
+        let table = format!("{}_{}_CONSTANTS", self.name.to_uppercase(), self.curve);
+        let constants: Vec<_> = circuit
+            .nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(id, node)| match node {
+                Node::Constant(value) => Some((id, *value)),
+                _ => None,
+            })
+            .collect();
+        let inputs: Vec<_> = circuit
+            .nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(id, node)| match node {
+                Node::Input(name) => Some((id, name.as_str())),
+                _ => None,
+            })
+            .collect();
+
+        let mut names = vec![String::new(); circuit.nodes.len()];
+        for (k, id) in constants
+            .iter()
+            .map(|(id, _)| *id)
+            .chain(inputs.iter().map(|(id, _)| *id))
+            .enumerate()
+        {
+            names[id] = format!("in{k}");
+        }
+
+        let mut out = String::new();
+        writeln!(out, "use core::circuit::{{").unwrap();
+        writeln!(
+            out,
+            "    u384, circuit_add, circuit_mul, EvalCircuitTrait, CircuitOutputsTrait, CircuitInputs,"
+        )
+        .unwrap();
+        writeln!(out, "}};").unwrap();
+        writeln!(out, "use garaga::core::circuit::AddInputResultTrait2;").unwrap();
+        writeln!(out, "use core::circuit::CircuitElement as CE;").unwrap();
+        writeln!(out, "use core::circuit::CircuitInput as CI;").unwrap();
+        writeln!(out, "use garaga::definitions::get_{}_modulus;", self.curve).unwrap();
+        writeln!(out).unwrap();
+
+        writeln!(out, "const {table}: [u384; {}] = [", constants.len()).unwrap();
+        for (_, value) in &constants {
+            writeln!(out, "    {},", U384::from(*value)).unwrap();
+        }
+        writeln!(out, "];").unwrap();
+        writeln!(out).unwrap();
+
+        let params = inputs
+            .iter()
+            .map(|(_, name)| format!("{name}: u384"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let returns = vec!["u384"; circuit.outputs.len()].join(", ");
+        let returns = match circuit.outputs.len() {
+            1 => returns,
+            _ => format!("({returns})"),
+        };
+        writeln!(out, "#[inline(always)]").unwrap();
+        writeln!(
+            out,
+            "pub fn run_{}_circuit({params}) -> {returns} {{",
+            self.name
+        )
+        .unwrap();
+        writeln!(out, "    // CONSTANT stack").unwrap();
+        for (id, value) in &constants {
+            let k = &names[*id][2..];
+            writeln!(
+                out,
+                "    let in{k} = CE::<CI<{k}>> {{}}; // {}",
+                hex(*value)
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+        writeln!(out, "    // INPUT stack").unwrap();
+        let input_names: Vec<_> = inputs.iter().map(|(id, _)| names[*id].clone()).collect();
+        let input_elements: Vec<_> = input_names
+            .iter()
+            .map(|name| format!("CE::<CI<{}>> {{}}", &name[2..]))
+            .collect();
+        writeln!(
+            out,
+            "    let ({}) = ({});",
+            input_names.join(", "),
+            input_elements.join(", ")
+        )
+        .unwrap();
+
+        let mut gates = 0;
+        for (id, node) in circuit.nodes.iter().enumerate() {
+            let (op, a, b) = match node {
+                Node::Add(a, b) => ("circuit_add", a, b),
+                Node::Mul(a, b) => ("circuit_mul", a, b),
+                _ => continue,
+            };
+            names[id] = format!("t{gates}");
+            gates += 1;
+            writeln!(
+                out,
+                "    let {} = {op}({}, {});",
+                names[id], names[*a], names[*b]
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+
+        writeln!(
+            out,
+            "    let modulus = get_{0}_modulus(); // {0} prime field modulus",
+            self.curve
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        let output_names: Vec<_> = circuit
+            .outputs
+            .iter()
+            .map(|(_, id)| names[*id].as_str())
+            .collect();
+        writeln!(
+            out,
+            "    let mut circuit_inputs = ({},).new_inputs();",
+            output_names.join(", ")
+        )
+        .unwrap();
+        writeln!(out, "    // Prefill constants:").unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "    circuit_inputs = circuit_inputs.next_span({table}.span()); // in0 - in{}",
+            constants.len() - 1
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "    // Fill inputs:").unwrap();
+        for ((_, name), in_name) in inputs.iter().zip(&input_names) {
+            writeln!(
+                out,
+                "    circuit_inputs = circuit_inputs.next_2({name}); // {in_name}"
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "    let outputs = circuit_inputs.done_2().eval(modulus).unwrap();"
+        )
+        .unwrap();
+        for ((name, _), node) in circuit.outputs.iter().zip(&output_names) {
+            writeln!(out, "    let {name}: u384 = outputs.get_output({node});").unwrap();
+        }
+        let results: Vec<_> = circuit
+            .outputs
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        match results.as_slice() {
+            [single] => writeln!(out, "    return {single};").unwrap(),
+            _ => writeln!(out, "    return ({});", results.join(", ")).unwrap(),
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+/// A circom template: products of two signals become constraints, everything else is
+/// folded into linear combinations.
+pub struct CircomBackend {
+    pub template: String,
+}
+
+impl Default for CircomBackend {
+    fn default() -> Self {
+        CircomBackend {
+            template: "PoseidonGrumpkin".to_string(),
+        }
+    }
+}
+
+impl CircomBackend {
+    fn is_constraint(node: &Node, constant: &[bool]) -> bool {
+        matches!(node, Node::Mul(a, b) if !constant[*a] && !constant[*b])
+    }
+}
+
+impl Backend for CircomBackend {
+    fn name(&self) -> &'static str {
+        "circom"
+    }
+
+    fn gate_count(&self, circuit: &Circuit) -> usize {
+        let constant = circuit.constant_nodes();
+        let products = circuit
+            .nodes
+            .iter()
+            .filter(|node| Self::is_constraint(node, &constant))
+            .count();
+        products + circuit.outputs.len()
+    }
+
+    fn emit(&self, circuit: &Circuit) -> String {
+        let constant = circuit.constant_nodes();
+        let values = circuit
+            .nodes
+            .iter()
+            .fold(Vec::new(), |mut values: Vec<Option<Fr>>, node| {
+                let value = match node {
+                    Node::Constant(value) => Some(*value),
+                    Node::Add(a, b) => values[*a].zip(values[*b]).map(|(a, b)| a + b),
+                    Node::Mul(a, b) => values[*a].zip(values[*b]).map(|(a, b)| a * b),
+                    Node::Input(_) => None,
+                };
+                values.push(value);
+                values
+            });
+
+        let mut out = String::new();
+        writeln!(out, "pragma circom 2.1.0;").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "template {}() {{", self.template).unwrap();
+        for node in &circuit.nodes {
+            if let Node::Input(name) = node {
+                writeln!(out, "    signal input {name};").unwrap();
+            }
+        }
+        for (name, _) in &circuit.outputs {
+            writeln!(out, "    signal output {name};").unwrap();
+        }
+        writeln!(out).unwrap();
+
+        let mut names = vec![String::new(); circuit.nodes.len()];
+        for (id, node) in circuit.nodes.iter().enumerate() {
+            names[id] = match (node, values[id]) {
+                (Node::Input(name), _) => name.clone(),
+                (_, Some(value)) => value.into_bigint().to_string(),
+                (Node::Add(a, b), None) => {
+                    writeln!(out, "    var t{id} = {} + {};", names[*a], names[*b]).unwrap();
+                    format!("t{id}")
+                }
+                (Node::Mul(a, b), None) if Self::is_constraint(node, &constant) => {
+                    writeln!(out, "    signal t{id} <== {} * {};", names[*a], names[*b]).unwrap();
+                    format!("t{id}")
+                }
+                (Node::Mul(a, b), None) => {
+                    writeln!(out, "    var t{id} = {} * {};", names[*a], names[*b]).unwrap();
+                    format!("t{id}")
+                }
+                (Node::Constant(_), None) => unreachable!("constants always have a value"),
+            };
+        }
+        writeln!(out).unwrap();
+        for (name, id) in &circuit.outputs {
+            writeln!(out, "    {name} <== {};", names[*id]).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        writeln!(out).unwrap();
+        let inputs: Vec<_> = circuit
+            .nodes
+            .iter()
+            .filter_map(|node| match node {
+                Node::Input(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        writeln!(
+            out,
+            "component main {{public [{}]}} = {}();",
+            inputs.join(", "),
+            self.template
+        )
+        .unwrap();
+        out
+    }
+}
+
+fn hex(value: Fr) -> String {
+    let U384 {
+        limb0,
+        limb1,
+        limb2,
+        ..
+    } = value.into();
+    match (limb2, limb1) {
+        (0, 0) => format!("{limb0:#x}"),
+        (0, _) => format!("{limb1:#x}{limb0:024x}"),
+        _ => format!("{limb2:#x}{limb1:024x}{limb0:024x}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ark_ff::{BigInt, Field};
+
+    use super::*;
+    use crate::functional::poseidon_hash;
+
+    const LIB_CAIRO: &str = include_str!("../../poseidon/src/lib.cairo");
+
+    /// Drops whitespace and the trailing commas `scarb fmt` adds when it wraps lines.
+    fn normalize(source: &str) -> String {
+        let compact: String = source.chars().filter(|c| !c.is_whitespace()).collect();
+        compact
+            .replace(",>", ">")
+            .replace(",}", "}")
+            .replace(",]", "]")
+            .replace(",)", ")")
+    }
+
+    #[test]
+    fn test_circuit_evaluate() {
+        let circuit = poseidon_circuit(&CircomPoseidonConstants::default());
+        let x = Fr::from_bigint(
+            BigInt::from_str(
+                "4919343374109933223627495853328117416310439472367144134356200729566675210393",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let y = Fr::from_bigint(
+            BigInt::from_str(
+                "5253109015430049131200882288700211126182357120559979176158196848667194692355",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(circuit.evaluate(&[x, y]), vec![poseidon_hash(x, y)]);
+    }
+
+    #[test]
+    fn test_circuit_constant_reuses_nodes() {
+        let mut circuit = Circuit::default();
+        let x = circuit.input("x");
+        let one = circuit.constant(Fr::ONE);
+        let sum = circuit.add(x, one);
+        assert_eq!(circuit.constant(Fr::ONE), one);
+        assert_ne!(circuit.constant(Fr::ZERO), one);
+        let again = circuit.constant(Fr::ONE);
+        circuit.mul(sum, again);
+        // x, 1, x + 1, 0 and the product.
+        assert_eq!(circuit.nodes().len(), 5);
+    }
+
+    #[test]
+    fn test_circuit_stats() {
+        let circuit = poseidon_circuit(&CircomPoseidonConstants::default());
+
+        assert_eq!(
+            circuit.stats(),
+            CircuitStats {
+                inputs: 2,
+                constants: 323,
+                adds: 353,
+                muls: 594,
+            }
+        );
+        assert_eq!(CairoBackend::default().gate_count(&circuit), 947);
+        assert_eq!(CircomBackend::default().gate_count(&circuit), 241);
+    }
+
+    #[test]
+    fn test_circuit_cairo_backend_matches_lib_cairo() {
+        let circuit = poseidon_circuit(&CircomPoseidonConstants::default());
+        let emitted = CairoBackend::default().emit(&circuit);
+        let circuit_source = &LIB_CAIRO[..LIB_CAIRO.find("#[cfg(test)]").unwrap()];
+
+        assert_eq!(normalize(&emitted), normalize(circuit_source));
+    }
+
+    #[test]
+    fn test_circuit_circom_backend() {
+        let circuit = poseidon_circuit(&CircomPoseidonConstants::default());
+        let emitted = CircomBackend::default().emit(&circuit);
+
+        assert_eq!(emitted.matches(" <== ").count(), 241);
+        assert!(emitted.contains("template PoseidonGrumpkin() {"));
+        assert!(emitted.ends_with("component main {public [x, y]} = PoseidonGrumpkin();\n"));
+    }
+}
@@ -7,3 +7,130 @@ use ark_ff::fields::{Fp256, MontBackend, MontConfig};
 #[small_subgroup_power = "2"]
 pub struct FrConfig;
 pub type Fr = Fp256<MontBackend<FrConfig, 4>>;
+
+#[cfg(test)]
+pub(crate) fn arb_fr() -> impl proptest::strategy::Strategy<Value = Fr> {
+    use ark_ff::PrimeField;
+    use proptest::prelude::*;
+
+    prop_oneof![
+        any::<[u8; 32]>().prop_map(|bytes| Fr::from_le_bytes_mod_order(&bytes)),
+        any::<u64>().prop_map(Fr::from),
+        any::<u64>().prop_map(|n| -Fr::from(n)),
+    ]
+}
+
+/// Serde representation of field elements as decimal strings, the way circom and
+/// `poseidon.py` print them. Hex strings with a `0x` prefix and plain JSON integers
+/// are accepted when reading.
+#[cfg(feature = "std")]
+pub mod decimal {
+    use std::str::FromStr;
+
+    use ark_ff::{BigInt, PrimeField};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use super::Fr;
+
+    pub fn to_string(value: &Fr) -> String {
+        value.into_bigint().to_string()
+    }
+
+    pub fn from_str(value: &str) -> Option<Fr> {
+        let value = value.trim();
+        let bigint = match value.strip_prefix("0x") {
+            Some(hex) => {
+                let hex = if hex.len() % 2 == 1 {
+                    format!("0x0{hex}")
+                } else {
+                    format!("0x{hex}")
+                };
+                let bytes = prefix_hex::decode::<Vec<u8>>(hex).ok()?;
+                if bytes.len() > 32 {
+                    return None;
+                }
+                let mut padded = [0; 32];
+                padded[32 - bytes.len()..].copy_from_slice(&bytes);
+                let mut words = [0; 4];
+                for (word, chunk) in words.iter_mut().zip(padded.rchunks(8)) {
+                    *word = u64::from_be_bytes(chunk.try_into().unwrap());
+                }
+                BigInt(words)
+            }
+            None => BigInt::from_str(value).ok()?,
+        };
+        Fr::from_bigint(bigint)
+    }
+
+    pub fn serialize<S: Serializer>(value: &Fr, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_string(value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Fr, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Number(u64),
+            String(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(value) => Ok(Fr::from(value)),
+            Repr::String(value) => from_str(&value).ok_or_else(|| {
+                de::Error::custom(format!("`{value}` is not a canonical field element"))
+            }),
+        }
+    }
+
+    pub mod vec {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        use super::Fr;
+
+        #[derive(Serialize, Deserialize)]
+        struct Decimal(#[serde(with = "super")] Fr);
+
+        pub fn serialize<S: Serializer>(values: &[Fr], serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(values.iter().map(|value| Decimal(*value)))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<Fr>, D::Error> {
+            Ok(Vec::<Decimal>::deserialize(deserializer)?
+                .into_iter()
+                .map(|Decimal(value)| value)
+                .collect())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use ark_ff::{BigInteger, PrimeField};
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn decimal_round_trip(value in arb_fr()) {
+            prop_assert_eq!(decimal::from_str(&decimal::to_string(&value)), Some(value));
+
+            let hex = prefix_hex::encode(value.into_bigint().to_bytes_be());
+            prop_assert_eq!(decimal::from_str(&hex), Some(value));
+        }
+
+        #[test]
+        fn decimal_rejects_non_canonical(offset in any::<u64>()) {
+            let mut modulus = Fr::MODULUS;
+            modulus.add_with_carry(&ark_ff::BigInt::from(offset));
+            prop_assert_eq!(decimal::from_str(&modulus.to_string()), None);
+        }
+
+        #[test]
+        fn decimal_never_panics(text in "(0x)?[0-9a-fA-F]{0,80}|\\PC{0,40}") {
+            let _ = decimal::from_str(&text);
+        }
+    }
+}
@@ -1,223 +1,259 @@
-use ark_ff::{AdditiveGroup, Field, PrimeField};
-
-use crate::{constants::CircomPoseidonConstants, field::Fr};
+use core::marker::PhantomData;
+
+use ark_ff::PrimeField;
+
+use crate::{
+    constants::{CircomPoseidonConstants, T},
+    field::Fr,
+    mont::Bn254,
+    permutation::{Arithmetic, PoseidonParameters},
+};
+#[cfg(feature = "std")]
+use crate::{
+    constants::{N_ROUNDS_F, N_ROUNDS_P},
+    trace::{Step, Trace},
+};
 
 pub fn poseidon_hash(x: Fr, y: Fr) -> Fr {
-    let constants = CircomPoseidonConstants::default();
-    let result = [Fr::default(), x, y].into_iter();
+    poseidon_hash_with_initial_state(Fr::default(), x, y)
+}
 
-    let state = Poseidon { constants, result };
+/// circomlib's `PoseidonEx(2, 1)`: the capacity element starts at `initial_state`.
+pub fn poseidon_hash_with_initial_state(initial_state: Fr, x: Fr, y: Fr) -> Fr {
+    poseidon_hash_with_constants(CircomPoseidonConstants::shared(), initial_state, x, y)
+}
 
-    state
-        .apply_ark(0)
-        .apply_first_half_of_full_rounds()
-        .apply_middle_round()
-        .apply_partial_rounds()
-        .apply_second_half_of_full_rounds()
-        .apply_sigma()
+/// Same as `poseidon_hash_with_initial_state`, with caller-provided constants.
+pub fn poseidon_hash_with_constants(
+    constants: &CircomPoseidonConstants,
+    initial_state: Fr,
+    x: Fr,
+    y: Fr,
+) -> Fr {
+    hash_with::<Fr, T, Bn254>(constants.parameters(), [initial_state, x, y])
+}
+
+/// The whole permutation, i.e. every output of circomlib's `PoseidonEx(2, 3)`: the last
+/// round mixes all lanes with `M` instead of only producing lane 0.
+pub fn poseidon_permutation(state: [Fr; T]) -> [Fr; T] {
+    poseidon_permutation_with_constants(CircomPoseidonConstants::shared(), state)
+}
+
+/// Same as `poseidon_permutation`, with caller-provided constants.
+pub fn poseidon_permutation_with_constants(
+    constants: &CircomPoseidonConstants,
+    state: [Fr; T],
+) -> [Fr; T] {
+    permute_with::<Fr, T, Bn254>(constants.parameters(), state)
+}
+
+/// Lane 0 of the permutation of `parameters`, computed with the arithmetic `A`.
+pub(crate) fn hash_with<F: PrimeField, const T: usize, A: Arithmetic<F>>(
+    parameters: PoseidonParameters<'_, F, T>,
+    state: [A::Elem; T],
+) -> A::Elem {
+    Poseidon::<F, T, A>::with_parameters(parameters, state)
+        .apply_rounds()
         .apply_mix_last(0)
 }
 
-struct Poseidon<T: Iterator<Item = Fr>> {
-    constants: CircomPoseidonConstants,
-    result: T,
+/// The permutation of `parameters`, computed with the arithmetic `A`.
+pub(crate) fn permute_with<F: PrimeField, const T: usize, A: Arithmetic<F>>(
+    parameters: PoseidonParameters<'_, F, T>,
+    state: [A::Elem; T],
+) -> [A::Elem; T] {
+    Poseidon::<F, T, A>::with_parameters(parameters, state)
+        .apply_rounds()
+        .apply_mix_m()
+        .state
 }
 
-impl<T: Iterator<Item = Fr>> Poseidon<T> {
-    fn apply_second_half_of_full_rounds(self) -> Poseidon<impl Iterator<Item = Fr>> {
-        self.apply_sigma()
-            .apply_ark((8 / 2 + 1) * 3 + 57)
-            .apply_mix_m()
-            .apply_sigma()
-            .apply_ark((8 / 2 + 1) * 3 + 57 + 3)
-            .apply_mix_m()
-            .apply_sigma()
-            .apply_ark((8 / 2 + 1) * 3 + 57 + 2 * 3)
-            .apply_mix_m()
-    }
-
-    fn apply_partial_rounds(self) -> Poseidon<impl Iterator<Item = Fr>> {
-        self.apply_partial_round(0)
-            .apply_partial_round(1)
-            .apply_partial_round(2)
-            .apply_partial_round(3)
-            .apply_partial_round(4)
-            .apply_partial_round(5)
-            .apply_partial_round(6)
-            .apply_partial_round(7)
-            .apply_partial_round(8)
-            .apply_partial_round(9)
-            .apply_partial_round(10)
-            .apply_partial_round(11)
-            .apply_partial_round(12)
-            .apply_partial_round(13)
-            .apply_partial_round(14)
-            .apply_partial_round(15)
-            .apply_partial_round(16)
-            .apply_partial_round(17)
-            .apply_partial_round(18)
-            .apply_partial_round(19)
-            .apply_partial_round(20)
-            .apply_partial_round(21)
-            .apply_partial_round(22)
-            .apply_partial_round(23)
-            .apply_partial_round(24)
-            .apply_partial_round(25)
-            .apply_partial_round(26)
-            .apply_partial_round(27)
-            .apply_partial_round(28)
-            .apply_partial_round(29)
-            .apply_partial_round(30)
-            .apply_partial_round(31)
-            .apply_partial_round(32)
-            .apply_partial_round(33)
-            .apply_partial_round(34)
-            .apply_partial_round(35)
-            .apply_partial_round(36)
-            .apply_partial_round(37)
-            .apply_partial_round(38)
-            .apply_partial_round(39)
-            .apply_partial_round(40)
-            .apply_partial_round(41)
-            .apply_partial_round(42)
-            .apply_partial_round(43)
-            .apply_partial_round(44)
-            .apply_partial_round(45)
-            .apply_partial_round(46)
-            .apply_partial_round(47)
-            .apply_partial_round(48)
-            .apply_partial_round(49)
-            .apply_partial_round(50)
-            .apply_partial_round(51)
-            .apply_partial_round(52)
-            .apply_partial_round(53)
-            .apply_partial_round(54)
-            .apply_partial_round(55)
-            .apply_partial_round(56)
-    }
-
-    fn apply_middle_round(self) -> Poseidon<impl Iterator<Item = Fr>> {
-        self.apply_sigma().apply_ark(4 * 3).apply_mix_p()
-    }
-
-    fn apply_first_half_of_full_rounds(self) -> Poseidon<impl Iterator<Item = Fr>> {
-        self.apply_sigma()
-            .apply_ark(3)
-            .apply_mix_m()
-            .apply_sigma()
-            .apply_ark(6)
-            .apply_mix_m()
-            .apply_sigma()
-            .apply_ark(9)
-            .apply_mix_m()
+/// Hashes every pair of `inputs`, as `poseidon_hash` would one at a time.
+#[cfg(feature = "std")]
+pub fn poseidon_hash_batch(inputs: &[[Fr; 2]]) -> Vec<Fr> {
+    let constants = CircomPoseidonConstants::shared();
+    inputs
+        .iter()
+        .map(|[x, y]| poseidon_hash_with_constants(constants, Fr::default(), *x, *y))
+        .collect()
+}
+
+/// Same as `poseidon_hash`, recording the state after every ARK, S-box and mix step.
+#[cfg(feature = "std")]
+pub fn poseidon_hash_traced(x: Fr, y: Fr) -> (Fr, Trace) {
+    let mut trace = Trace::new(&[x, y]);
+    let mut state = Poseidon::new(CircomPoseidonConstants::shared(), [Fr::default(), x, y]);
+
+    state.apply_ark(0).record(&mut trace, 0, Step::Ark);
+    for r in 0..N_ROUNDS_F / 2 {
+        state.apply_sigma().record(&mut trace, r, Step::Sbox);
+        state
+            .apply_ark((r + 1) * T)
+            .record(&mut trace, r, Step::Ark);
+        if r < N_ROUNDS_F / 2 - 1 {
+            state.apply_mix_m().record(&mut trace, r, Step::Mix);
+        } else {
+            state.apply_mix_p().record(&mut trace, r, Step::Mix);
+        }
+    }
+    for r in 0..N_ROUNDS_P {
+        let round = N_ROUNDS_F / 2 + r;
+        state
+            .apply_partial_sigma()
+            .record(&mut trace, round, Step::Sbox);
+        state
+            .apply_partial_ark(r)
+            .record(&mut trace, round, Step::Ark);
+        state.apply_mix_s(r).record(&mut trace, round, Step::Mix);
+    }
+    for r in 0..N_ROUNDS_F / 2 - 1 {
+        let round = N_ROUNDS_F / 2 + N_ROUNDS_P + r;
+        state.apply_sigma().record(&mut trace, round, Step::Sbox);
+        state
+            .apply_ark((N_ROUNDS_F / 2 + 1) * T + N_ROUNDS_P + r * T)
+            .record(&mut trace, round, Step::Ark);
+        state.apply_mix_m().record(&mut trace, round, Step::Mix);
     }
+    let round = N_ROUNDS_F + N_ROUNDS_P - 1;
+    let result = state
+        .apply_sigma()
+        .record(&mut trace, round, Step::Sbox)
+        .apply_mix_last(0);
+    trace.push(round, Step::MixLast, &[result]);
+
+    (result, trace)
+}
+
+/// The permutation state: `T` lanes updated in place, with borrowed constants. `A`
+/// provides the arithmetic and what a lane holds; the defaults are the circomlib BN254
+/// instance.
+struct Poseidon<'a, F: PrimeField = Fr, const T: usize = 3, A: Arithmetic<F> = Bn254> {
+    parameters: PoseidonParameters<'a, F, T>,
+    state: [A::Elem; T],
+    arithmetic: PhantomData<A>,
+}
+
+impl<'a> Poseidon<'a> {
+    #[cfg(any(feature = "std", test))]
+    fn new(constants: &'a CircomPoseidonConstants, state: [Fr; 3]) -> Self {
+        Poseidon::with_parameters(constants.parameters(), state)
+    }
+
+    #[cfg(feature = "std")]
+    fn record(&mut self, trace: &mut Trace, round: usize, step: Step) -> &mut Self {
+        trace.push(round, step, &self.state);
+        self
+    }
+}
 
-    fn apply_sigma(self) -> Poseidon<impl Iterator<Item = Fr>> {
-        let result = self.result.map(|t| t.pow([5]));
+impl<'a, F: PrimeField, const T: usize, A: Arithmetic<F>> Poseidon<'a, F, T, A> {
+    fn with_parameters(parameters: PoseidonParameters<'a, F, T>, state: [A::Elem; T]) -> Self {
         Poseidon {
-            constants: self.constants,
-            result,
+            parameters,
+            state,
+            arithmetic: PhantomData,
         }
     }
 
-    fn apply_ark(self, r: usize) -> Poseidon<impl Iterator<Item = Fr>> {
-        let result = self
-            .result
-            .enumerate()
-            .map(move |(i, x)| x + self.constants.c[i + r]);
-        Poseidon {
-            constants: self.constants,
-            result,
+    /// Every round, up to the final mix.
+    fn apply_rounds(&mut self) -> &mut Self {
+        self.apply_ark(0)
+            .apply_first_half_of_full_rounds()
+            .apply_middle_round()
+            .apply_partial_rounds()
+            .apply_second_half_of_full_rounds()
+            .apply_sigma()
+    }
+
+    fn apply_second_half_of_full_rounds(&mut self) -> &mut Self {
+        let (rounds_f, rounds_p) = (self.parameters.rounds_f, self.parameters.rounds_p);
+        for r in 0..rounds_f / 2 - 1 {
+            self.apply_sigma()
+                .apply_ark((rounds_f / 2 + 1) * T + rounds_p + r * T)
+                .apply_mix_m();
         }
+        self
     }
 
-    fn apply_mix_m(self) -> Poseidon<impl Iterator<Item = Fr>> {
-        let result = self
-            .result
-            .enumerate()
-            .fold([Fr::ZERO, Fr::ZERO, Fr::ZERO], |acc, (i, val)| {
-                [
-                    acc[0] + val * self.constants.m[i][0],
-                    acc[1] + val * self.constants.m[i][1],
-                    acc[2] + val * self.constants.m[i][2],
-                ]
-            })
-            .into_iter();
-        Poseidon {
-            constants: self.constants,
-            result,
+    fn apply_partial_rounds(&mut self) -> &mut Self {
+        for r in 0..self.parameters.rounds_p {
+            self.apply_partial_round(r);
         }
+        self
     }
 
-    fn apply_mix_p(self) -> Poseidon<impl Iterator<Item = Fr>> {
-        let result = self
-            .result
-            .enumerate()
-            .fold([Fr::ZERO, Fr::ZERO, Fr::ZERO], |acc, (i, val)| {
-                [
-                    acc[0] + val * self.constants.p[i][0],
-                    acc[1] + val * self.constants.p[i][1],
-                    acc[2] + val * self.constants.p[i][2],
-                ]
-            })
-            .into_iter();
-        Poseidon {
-            constants: self.constants,
-            result,
+    fn apply_middle_round(&mut self) -> &mut Self {
+        let r = self.parameters.rounds_f / 2 * T;
+        self.apply_sigma().apply_ark(r).apply_mix_p()
+    }
+
+    fn apply_first_half_of_full_rounds(&mut self) -> &mut Self {
+        for r in 0..self.parameters.rounds_f / 2 - 1 {
+            self.apply_sigma().apply_ark((r + 1) * T).apply_mix_m();
         }
+        self
     }
 
-    fn apply_mix_s(self, r: usize) -> Poseidon<impl Iterator<Item = Fr>> {
-        let result = self
-            .result
-            .enumerate()
-            .fold([Fr::ZERO, Fr::ZERO, Fr::ZERO], |acc, (i, val)| {
-                let i_f = Fr::from_be_bytes_mod_order(&i.to_be_bytes());
-                [
-                    acc[0] + val * self.constants.s[(3 * 2 - 1) * r + i],
-                    acc[1]
-                        + val
-                            * (self.constants.s[(3 * 2 - 1) * r + 3 + 1 - 1]
-                                * (i_f - Fr::from(1))
-                                * (i_f - Fr::from(2))
-                                / Fr::from(2)
-                                - i_f * (i_f - Fr::from(2))),
-                    acc[2]
-                        + val
-                            * (self.constants.s[(3 * 2 - 1) * r + 3 + 2 - 1]
-                                * (i_f - Fr::from(1))
-                                * (i_f - Fr::from(2))
-                                / Fr::from(2)
-                                + i_f * (i_f - Fr::from(1)) / Fr::from(2)),
-                ]
-            })
-            .into_iter();
-        Poseidon {
-            constants: self.constants,
-            result,
+    fn apply_sigma(&mut self) -> &mut Self {
+        for val in &mut self.state {
+            *val = A::sbox(val, self.parameters.alpha);
         }
+        self
     }
 
-    fn apply_partial_round(self, r: usize) -> Poseidon<impl Iterator<Item = Fr>> {
-        let c = self.constants.c;
-        let result = self.result.enumerate().map(move |(i, val)| {
-            let i_f = Fr::from_be_bytes_mod_order(&i.to_be_bytes());
-            ((i_f - Fr::from(1)) * (i_f - Fr::from(2)) / Fr::from(2))
-                * (val.pow([5]) + c[(8 / 2 + 1) * 3 + r])
-                + (-i_f * (i_f - Fr::from(2))) * val
-                + (i_f * (i_f - Fr::from(1)) / Fr::from(2)) * val
-        });
-        Poseidon {
-            constants: self.constants,
-            result,
+    fn apply_ark(&mut self, r: usize) -> &mut Self {
+        for (val, c) in self.state.iter_mut().zip(&self.parameters.c[r..r + T]) {
+            *val = A::add_constant(val, c);
+        }
+        self
+    }
+
+    fn apply_mix_m(&mut self) -> &mut Self {
+        self.state = A::mix(&self.state, self.parameters.m);
+        self
+    }
+
+    fn apply_mix_p(&mut self) -> &mut Self {
+        self.state = A::mix(&self.state, self.parameters.p);
+        self
+    }
+
+    /// The sparse partial-round matrix: lane 0 takes a full row, every other lane `k`
+    /// only adds `state[0] * s[(2T - 1) r + T + k - 1]` to itself.
+    fn apply_mix_s(&mut self, r: usize) -> &mut Self {
+        let s = &self.parameters.s[(T * 2 - 1) * r..(T * 2 - 1) * (r + 1)];
+        let x0 = self.state[0];
+        self.state[0] = self.state[1..]
+            .iter()
+            .zip(&s[1..T])
+            .fold(A::mul(&x0, &s[0]), |acc, (val, s)| {
+                A::add(&acc, &A::mul(val, s))
+            });
+        for (val, s) in self.state[1..].iter_mut().zip(&s[T..]) {
+            *val = A::add(val, &A::mul(&x0, s));
         }
-        .apply_mix_s(r)
+        self
     }
 
-    fn apply_mix_last(self, s: usize) -> Fr {
-        self.result
-            .enumerate()
-            .fold(Fr::ZERO, |acc, (i, val)| acc + val * self.constants.m[i][s])
+    fn apply_partial_round(&mut self, r: usize) -> &mut Self {
+        self.apply_partial_sigma()
+            .apply_partial_ark(r)
+            .apply_mix_s(r)
+    }
+
+    fn apply_partial_sigma(&mut self) -> &mut Self {
+        self.state[0] = A::sbox(&self.state[0], self.parameters.alpha);
+        self
+    }
+
+    fn apply_partial_ark(&mut self, r: usize) -> &mut Self {
+        let c = &self.parameters.c[(self.parameters.rounds_f / 2 + 1) * T + r];
+        self.state[0] = A::add_constant(&self.state[0], c);
+        self
+    }
+
+    fn apply_mix_last(&self, s: usize) -> A::Elem {
+        A::mix_last(&self.state, self.parameters.m, s)
     }
 }
 
@@ -225,7 +261,7 @@ impl<T: Iterator<Item = Fr>> Poseidon<T> {
 mod tests {
     use std::str::FromStr;
 
-    use ark_ff::{BigInt, PrimeField};
+    use ark_ff::{AdditiveGroup, BigInt, Field, PrimeField};
 
     use super::*;
 
@@ -254,11 +290,10 @@ mod tests {
                 .unwrap(),
             )
             .unwrap(),
-        ]
-        .into_iter();
+        ];
 
-        let state = Poseidon { constants, result };
-        let result: Vec<Fr> = state.apply_ark(0).result.collect();
+        let mut state = Poseidon::new(&constants, result);
+        let result = state.apply_ark(0).state;
 
         let expected_result = [
             Fr::from_bigint(
@@ -311,11 +346,10 @@ mod tests {
                 .unwrap(),
             )
             .unwrap(),
-        ]
-        .into_iter();
+        ];
 
-        let state = Poseidon { constants, result };
-        let result: Vec<Fr> = state.apply_mix_m().result.collect();
+        let mut state = Poseidon::new(&constants, result);
+        let result = state.apply_mix_m().state;
 
         let expected_result = [
             Fr::from_bigint(
@@ -368,11 +402,10 @@ mod tests {
                 .unwrap(),
             )
             .unwrap(),
-        ]
-        .into_iter();
+        ];
 
-        let state = Poseidon { constants, result };
-        let result: Vec<Fr> = state.apply_mix_p().result.collect();
+        let mut state = Poseidon::new(&constants, result);
+        let result = state.apply_mix_p().state;
 
         let expected_result = [
             Fr::from_bigint(
@@ -425,11 +458,10 @@ mod tests {
                 .unwrap(),
             )
             .unwrap(),
-        ]
-        .into_iter();
+        ];
 
-        let state = Poseidon { constants, result };
-        let result: Vec<Fr> = state.apply_mix_s(0).result.collect();
+        let mut state = Poseidon::new(&constants, result);
+        let result = state.apply_mix_s(0).state;
 
         let expected_result = [
             Fr::from_bigint(
@@ -482,10 +514,9 @@ mod tests {
                 .unwrap(),
             )
             .unwrap(),
-        ]
-        .into_iter();
+        ];
 
-        let state = Poseidon { constants, result };
+        let state = Poseidon::new(&constants, result);
         let result = state.apply_mix_last(0);
 
         let expected_result = Fr::from_bigint(
@@ -523,11 +554,10 @@ mod tests {
                 .unwrap(),
             )
             .unwrap(),
-        ]
-        .into_iter();
+        ];
 
-        let state = Poseidon { constants, result };
-        let result: Vec<Fr> = state.apply_first_half_of_full_rounds().result.collect();
+        let mut state = Poseidon::new(&constants, result);
+        let result = state.apply_first_half_of_full_rounds().state;
 
         let expected_result = [
             Fr::from_bigint(
@@ -580,11 +610,10 @@ mod tests {
                 .unwrap(),
             )
             .unwrap(),
-        ]
-        .into_iter();
+        ];
 
-        let state = Poseidon { constants, result };
-        let result: Vec<Fr> = state.apply_middle_round().result.collect();
+        let mut state = Poseidon::new(&constants, result);
+        let result = state.apply_middle_round().state;
 
         let expected_result = [
             Fr::from_bigint(
@@ -637,11 +666,10 @@ mod tests {
                 .unwrap(),
             )
             .unwrap(),
-        ]
-        .into_iter();
+        ];
 
-        let state = Poseidon { constants, result };
-        let result: Vec<Fr> = state.apply_partial_rounds().result.collect();
+        let mut state = Poseidon::new(&constants, result);
+        let result = state.apply_partial_rounds().state;
 
         let expected_result = [
             Fr::from_bigint(
@@ -694,11 +722,10 @@ mod tests {
                 .unwrap(),
             )
             .unwrap(),
-        ]
-        .into_iter();
+        ];
 
-        let state = Poseidon { constants, result };
-        let result: Vec<Fr> = state.apply_second_half_of_full_rounds().result.collect();
+        let mut state = Poseidon::new(&constants, result);
+        let result = state.apply_second_half_of_full_rounds().state;
 
         let expected_result = [
             Fr::from_bigint(
@@ -755,4 +782,126 @@ mod tests {
         .unwrap();
         assert_eq!(result, expected_result);
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_functional_poseidon_traced() {
+        let (result, trace) = poseidon_hash_traced(Fr::from(1), Fr::from(2));
+
+        assert_eq!(result, poseidon_hash(Fr::from(1), Fr::from(2)));
+        assert_eq!(trace.steps.len(), 1 + 3 * (8 + 57 - 1) + 2);
+        assert_eq!(trace.output, result);
+
+        let last = trace.steps.last().unwrap();
+        assert_eq!((last.round, last.step), (64, Step::MixLast));
+        assert_eq!(last.state, vec![result]);
+
+        let middle = &trace.steps[3 * 4];
+        assert_eq!((middle.round, middle.step), (3, Step::Mix));
+    }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use super::*;
+        #[cfg(feature = "std")]
+        use crate::circuit::poseidon_circuit;
+        use crate::field::arb_fr;
+
+        fn state(values: [Fr; 3]) -> Poseidon<'static> {
+            Poseidon::new(CircomPoseidonConstants::shared(), values)
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(16))]
+
+            #[cfg(feature = "std")]
+            #[test]
+            fn hash_matches_circuit(x in arb_fr(), y in arb_fr()) {
+                let circuit = poseidon_circuit(&CircomPoseidonConstants::default());
+                prop_assert_eq!(circuit.evaluate(&[x, y]), vec![poseidon_hash(x, y)]);
+            }
+
+            #[cfg(feature = "std")]
+            #[test]
+            fn hash_matches_trace(x in arb_fr(), y in arb_fr()) {
+                let (result, trace) = poseidon_hash_traced(x, y);
+                prop_assert_eq!(result, poseidon_hash(x, y));
+                prop_assert_eq!(trace.output, result);
+            }
+
+            #[test]
+            fn permutation_extends_hash(values in proptest::array::uniform3(arb_fr())) {
+                let [initial_state, x, y] = values;
+                let result = poseidon_permutation(values);
+                prop_assert_eq!(result[0], poseidon_hash_with_initial_state(initial_state, x, y));
+
+                let before_mix = state(values)
+                    .apply_ark(0)
+                    .apply_first_half_of_full_rounds()
+                    .apply_middle_round()
+                    .apply_partial_rounds()
+                    .apply_second_half_of_full_rounds()
+                    .apply_sigma()
+                    .state;
+                let lanes: [Fr; 3] = core::array::from_fn(|s| state(before_mix).apply_mix_last(s));
+                prop_assert_eq!(result, lanes);
+            }
+
+            #[test]
+            fn mix_s_is_sparse(values in proptest::array::uniform3(arb_fr()), r in 0..57usize) {
+                let s = CircomPoseidonConstants::default().s;
+                let result = state(values).apply_mix_s(r).state;
+
+                let [x0, x1, x2] = values;
+                let expected = [
+                    s[5 * r] * x0 + s[5 * r + 1] * x1 + s[5 * r + 2] * x2,
+                    x1 + x0 * s[5 * r + 3],
+                    x2 + x0 * s[5 * r + 4],
+                ];
+                prop_assert_eq!(result, expected);
+            }
+
+            #[test]
+            fn mix_s_matches_python_selectors(values in proptest::array::uniform3(arb_fr()), r in 0..57usize) {
+                // `poseidon.py`'s `mix_s`, with the selector polynomials evaluated as integers.
+                let s = CircomPoseidonConstants::shared().s;
+                let mut expected = [Fr::ZERO; 3];
+                for (i, val) in values.iter().enumerate() {
+                    let i = i as i64;
+                    let select_0 = Fr::from((i - 1) * (i - 2) / 2);
+                    expected[0] += *val * s[5 * r + i as usize];
+                    expected[1] += *val * (Fr::from(-i * (i - 2)) + s[5 * r + 3] * select_0);
+                    expected[2] += *val * (Fr::from(i * (i - 1) / 2) + s[5 * r + 4] * select_0);
+                }
+                prop_assert_eq!(state(values).apply_mix_s(r).state, expected);
+            }
+
+            #[test]
+            fn partial_round_only_touches_lane_0(values in proptest::array::uniform3(arb_fr()), r in 0..57usize) {
+                let c = CircomPoseidonConstants::default().c;
+                let result = state(values)
+                    .apply_partial_sigma()
+                    .apply_partial_ark(r)
+                    .state;
+
+                let [x0, x1, x2] = values;
+                prop_assert_eq!(result, [x0.pow([5]) + c[15 + r], x1, x2]);
+            }
+
+            #[test]
+            fn mix_m_is_linear(
+                a in proptest::array::uniform3(arb_fr()),
+                b in proptest::array::uniform3(arb_fr()),
+            ) {
+                let sum = [a[0] + b[0], a[1] + b[1], a[2] + b[2]];
+                let mix_a = state(a).apply_mix_m().state;
+                let mix_b = state(b).apply_mix_m().state;
+                let mix_sum = state(sum).apply_mix_m().state;
+
+                let expected = [mix_a[0] + mix_b[0], mix_a[1] + mix_b[1], mix_a[2] + mix_b[2]];
+                prop_assert_eq!(mix_sum, expected);
+            }
+        }
+    }
 }
@@ -0,0 +1,471 @@
+//! Poseidon instances generated the way the reference implementation does
+//! (`generate_parameters_grain.sage` from the Poseidon authors), and their conversion to
+//! the optimized form `PoseidonParameters` runs.
+//!
+//! The round constants and the Cauchy MDS matrix are drawn from a Grain LFSR seeded with
+//! the field size, the width and the round numbers. The reference script also rejects
+//! MDS matrices with invariant subspaces and draws again; that check is not repeated
+//! here, so every instance this crate ships is pinned to the reference's published
+//! tables in its tests.
+
+use std::sync::OnceLock;
+
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::permutation::{Arithmetic, Ark, Permutation, PoseidonParameters};
+
+/// The seed's S-box field (see `Grain::new`) the reference script writes for `x^alpha`.
+/// circomlib's, halo2's and the published Poseidon2 constants were generated with it.
+pub const SBOX_REFERENCE: u8 = 0;
+/// The seed's S-box field the Poseidon tables published with zkhash, and neptune's, were
+/// generated with for the same `x^alpha`.
+pub const SBOX_ZKHASH: u8 = 1;
+
+/// The reference's Grain LFSR, in self-shrinking mode.
+pub struct Grain {
+    /// Bit `i` is the `i`-th oldest of the 80 register bits.
+    state: u128,
+}
+
+impl Grain {
+    /// Seeds the register for a prime field of `field_bits` bits.
+    ///
+    /// `sbox` is the seed's S-box field, `SBOX_REFERENCE` or `SBOX_ZKHASH` depending on
+    /// whose tables are reproduced.
+    pub fn new(sbox: u8, field_bits: u32, t: usize, rounds_f: usize, rounds_p: usize) -> Self {
+        // field type (1: prime), S-box, n, t, R_F, R_P, and 30 ones.
+        let fields = [
+            (1, 2),
+            (sbox as u128, 4),
+            (field_bits as u128, 12),
+            (t as u128, 12),
+            (rounds_f as u128, 10),
+            (rounds_p as u128, 10),
+            ((1 << 30) - 1, 30),
+        ];
+        let mut state = 0;
+        let mut position = 0;
+        for (value, width) in fields {
+            for i in (0..width).rev() {
+                state |= (value >> i & 1) << position;
+                position += 1;
+            }
+        }
+        let mut grain = Grain { state };
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let s = self.state;
+        let bit = (s >> 62 ^ s >> 51 ^ s >> 38 ^ s >> 23 ^ s >> 13 ^ s) & 1;
+        self.state = s >> 1 | bit << 79;
+        bit == 1
+    }
+
+    /// Draws bits in pairs and keeps the second of every pair whose first bit is set.
+    pub fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.step();
+            let bit = self.step();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// The next `n` bits, most significant first.
+    pub fn next_bigint<B: BigInteger>(&mut self, n: u32) -> B {
+        let bits: Vec<bool> = (0..n).map(|_| self.next_bit()).collect();
+        B::from_bits_be(&bits)
+    }
+
+    /// A uniform field element: field-sized integers are drawn until one is below the
+    /// modulus.
+    pub fn next_field_element<F: PrimeField>(&mut self) -> F {
+        loop {
+            if let Some(value) = F::from_bigint(self.next_bigint(F::MODULUS_BIT_SIZE)) {
+                return value;
+            }
+        }
+    }
+
+    /// A field-sized integer reduced modulo the field order, as the MDS entries are.
+    pub fn next_reduced<F: PrimeField>(&mut self) -> F {
+        let mut value: F::BigInt = self.next_bigint(F::MODULUS_BIT_SIZE);
+        if value >= F::MODULUS {
+            value.sub_with_borrow(&F::MODULUS);
+        }
+        F::from_bigint(value).expect("below twice the modulus")
+    }
+}
+
+/// A Poseidon instance in the reference implementation's form: every round adds `T`
+/// constants, and the MDS matrix `mds` maps `state` to `mds · state`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReferenceParameters<F, const T: usize> {
+    pub alpha: u64,
+    pub rounds_f: usize,
+    pub rounds_p: usize,
+    pub round_constants: Vec<[F; T]>,
+    pub mds: [[F; T]; T],
+}
+
+impl<F: PrimeField, const T: usize> ReferenceParameters<F, T> {
+    /// The constants the reference script prints for these parameters and the seed's
+    /// `sbox` field (see `Grain::new`): the round constants first, then the MDS matrix
+    /// from the same stream.
+    pub fn generate(sbox: u8, alpha: u64, rounds_f: usize, rounds_p: usize) -> Self {
+        let mut grain = Grain::new(sbox, F::MODULUS_BIT_SIZE, T, rounds_f, rounds_p);
+        let round_constants = (0..rounds_f + rounds_p)
+            .map(|_| core::array::from_fn(|_| grain.next_field_element()))
+            .collect();
+
+        let mds = loop {
+            let xs: [F; T];
+            let ys: [F; T];
+            loop {
+                let drawn: Vec<F> = (0..2 * T).map(|_| grain.next_reduced()).collect();
+                let distinct = drawn
+                    .iter()
+                    .enumerate()
+                    .all(|(i, x)| !drawn[..i].contains(x));
+                if distinct {
+                    xs = core::array::from_fn(|i| drawn[i]);
+                    ys = core::array::from_fn(|i| drawn[T + i]);
+                    break;
+                }
+            }
+            let entries: Option<Vec<[F; T]>> = xs
+                .iter()
+                .map(|x| {
+                    let row: Option<Vec<F>> = ys.iter().map(|y| (*x + y).inverse()).collect();
+                    row.map(|row| core::array::from_fn(|j| row[j]))
+                })
+                .collect();
+            if let Some(entries) = entries {
+                break core::array::from_fn(|i| entries[i]);
+            }
+        };
+
+        ReferenceParameters {
+            alpha,
+            rounds_f,
+            rounds_p,
+            round_constants,
+            mds,
+        }
+    }
+
+    /// The permutation exactly as the reference writes it, round by round.
+    pub fn permute(&self, mut state: [F; T]) -> [F; T] {
+        let half = self.rounds_f / 2;
+        for (r, constants) in self.round_constants.iter().enumerate() {
+            for (val, c) in state.iter_mut().zip(constants) {
+                *val += c;
+            }
+            if r < half || r >= half + self.rounds_p {
+                state = state.map(|val| Ark::sbox(&val, self.alpha));
+            } else {
+                state[0] = Ark::sbox(&state[0], self.alpha);
+            }
+            state = mul_vector(&self.mds, &state);
+        }
+        state
+    }
+
+    /// Whether `instance` permutes like these parameters, on a few fixed states.
+    pub fn agrees_with(&self, instance: &impl Permutation<T, Field = F>) -> bool {
+        (0..4u64).all(|seed| {
+            let state = core::array::from_fn(|i| F::from(seed * 1000 + i as u64) - F::from(7u64));
+            let mut permuted = state;
+            instance.permute(&mut permuted);
+            permuted == self.permute(state)
+        })
+    }
+
+    /// Moves the constants and factors the MDS matrix into circomlib's optimized form.
+    ///
+    /// Constants added right after a mix are moved in front of it through `mds⁻¹`. In the
+    /// partial rounds, only lane 0 of such a constant has to stay after the S-box; the rest
+    /// moves on to the round before, and the first full round of the second half gives up
+    /// its constants the same way. Each partial round's matrix is split into a sparse
+    /// matrix applied last and a matrix that leaves lane 0 alone, which commutes with the
+    /// S-box and is merged into the round before; the last one left over makes `p`.
+    pub fn optimize(&self) -> OptimizedConstants<F, T> {
+        let (half, rounds_p) = (self.rounds_f / 2, self.rounds_p);
+        let rc = &self.round_constants;
+        let mds_inverse = invert(&self.mds).expect("an MDS matrix is invertible");
+
+        let mut c: Vec<F> = rc[0].to_vec();
+        for constants in &rc[1..half] {
+            c.extend(mul_vector(&mds_inverse, constants));
+        }
+        let mut carried = rc[half + rounds_p];
+        let mut partial = vec![F::ZERO; rounds_p];
+        for r in (0..rounds_p).rev() {
+            let moved = mul_vector(&mds_inverse, &carried);
+            partial[r] = moved[0];
+            carried = rc[half + r];
+            for (val, m) in carried[1..].iter_mut().zip(&moved[1..]) {
+                *val += m;
+            }
+        }
+        c.extend(mul_vector(&mds_inverse, &carried));
+        c.extend(partial);
+        for constants in &rc[half + rounds_p + 1..] {
+            c.extend(mul_vector(&mds_inverse, constants));
+        }
+
+        let mut s = vec![F::ZERO; (2 * T - 1) * rounds_p];
+        let mut leftover = identity::<F, T>();
+        for r in (0..rounds_p).rev() {
+            let dense = mul_matrix(&leftover, &self.mds);
+            let inner: Vec<Vec<F>> = dense[1..].iter().map(|row| row[1..].to_vec()).collect();
+            let inner_inverse = invert_vec(inner).expect("MDS submatrices are invertible");
+
+            let entries = &mut s[(2 * T - 1) * r..(2 * T - 1) * (r + 1)];
+            entries[0] = dense[0][0];
+            for j in 1..T {
+                entries[j] = (1..T)
+                    .map(|i| dense[0][i] * inner_inverse[i - 1][j - 1])
+                    .sum();
+                entries[T + j - 1] = dense[j][0];
+            }
+            leftover = identity();
+            for i in 1..T {
+                leftover[i][1..].copy_from_slice(&dense[i][1..]);
+            }
+        }
+        let p = mul_matrix(&leftover, &self.mds);
+
+        OptimizedConstants {
+            alpha: self.alpha,
+            rounds_f: self.rounds_f,
+            rounds_p,
+            c,
+            m: transpose(&self.mds),
+            p: transpose(&p),
+            s,
+        }
+    }
+}
+
+/// Owned tables in the form `PoseidonParameters` borrows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OptimizedConstants<F, const T: usize> {
+    pub alpha: u64,
+    pub rounds_f: usize,
+    pub rounds_p: usize,
+    pub c: Vec<F>,
+    pub m: [[F; T]; T],
+    pub p: [[F; T]; T],
+    pub s: Vec<F>,
+}
+
+impl<F: PrimeField, const T: usize> OptimizedConstants<F, T> {
+    pub fn parameters(&self) -> PoseidonParameters<'_, F, T> {
+        PoseidonParameters::new(
+            self.alpha,
+            self.rounds_f,
+            self.rounds_p,
+            &self.c,
+            &self.m,
+            &self.p,
+            &self.s,
+        )
+    }
+}
+
+/// An instance whose constants are generated and optimized the first time `cell` is read,
+/// for the functions that hand out a crate's instances. Panics if the optimized form does
+/// not permute like the generated parameters.
+pub fn cached<F: PrimeField, const T: usize>(
+    cell: &'static OnceLock<OptimizedConstants<F, T>>,
+    generate: impl FnOnce() -> ReferenceParameters<F, T>,
+) -> PoseidonParameters<'static, F, T> {
+    cell.get_or_init(|| {
+        let reference = generate();
+        let optimized = reference.optimize();
+        assert!(
+            reference.agrees_with(&optimized.parameters()),
+            "the optimized constants disagree with the reference"
+        );
+        optimized
+    })
+    .parameters()
+}
+
+/// Parses a `0x`-prefixed big-endian test constant, which must be below the modulus.
+#[cfg(test)]
+pub(crate) fn hex<F: PrimeField>(value: &str) -> F {
+    let value = value.strip_prefix("0x").expect("missing 0x prefix");
+    let value = format!("{}{value}", "0".repeat(value.len() % 2));
+    let bytes: Vec<u8> = (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+        .collect();
+    let element = F::from_be_bytes_mod_order(&bytes);
+    let canonical = element.into_bigint().to_bytes_be();
+    let significant = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .copied()
+            .skip_while(|&b| b == 0)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        significant(&canonical),
+        significant(&bytes),
+        "{value} is not reduced"
+    );
+    element
+}
+
+fn identity<F: PrimeField, const T: usize>() -> [[F; T]; T] {
+    core::array::from_fn(|i| core::array::from_fn(|j| if i == j { F::ONE } else { F::ZERO }))
+}
+
+fn transpose<F: Copy, const T: usize>(matrix: &[[F; T]; T]) -> [[F; T]; T] {
+    core::array::from_fn(|i| core::array::from_fn(|j| matrix[j][i]))
+}
+
+fn mul_vector<F: PrimeField, const T: usize>(matrix: &[[F; T]; T], vector: &[F; T]) -> [F; T] {
+    core::array::from_fn(|i| matrix[i].iter().zip(vector).map(|(a, b)| *a * b).sum())
+}
+
+fn mul_matrix<F: PrimeField, const T: usize>(a: &[[F; T]; T], b: &[[F; T]; T]) -> [[F; T]; T] {
+    core::array::from_fn(|i| core::array::from_fn(|j| (0..T).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+fn invert<F: PrimeField, const T: usize>(matrix: &[[F; T]; T]) -> Option<[[F; T]; T]> {
+    let inverse = invert_vec(matrix.iter().map(|row| row.to_vec()).collect())?;
+    Some(core::array::from_fn(|i| {
+        core::array::from_fn(|j| inverse[i][j])
+    }))
+}
+
+/// Gauss-Jordan elimination; `None` if the matrix is singular.
+fn invert_vec<F: PrimeField>(mut matrix: Vec<Vec<F>>) -> Option<Vec<Vec<F>>> {
+    let n = matrix.len();
+    let mut inverse: Vec<Vec<F>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| if i == j { F::ONE } else { F::ZERO })
+                .collect()
+        })
+        .collect();
+    for col in 0..n {
+        let pivot = (col..n).find(|&row| !matrix[row][col].is_zero())?;
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+        let scale = matrix[col][col].inverse()?;
+        for j in 0..n {
+            matrix[col][j] *= scale;
+            inverse[col][j] *= scale;
+        }
+        for row in 0..n {
+            if row == col || matrix[row][col].is_zero() {
+                continue;
+            }
+            let factor = matrix[row][col];
+            for j in 0..n {
+                let (m, i) = (matrix[col][j], inverse[col][j]);
+                matrix[row][j] -= factor * m;
+                inverse[row][j] -= factor * i;
+            }
+        }
+    }
+    Some(inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{
+        constants::{CircomPoseidonConstants, N_ROUNDS_F, N_ROUNDS_P, T},
+        field::{arb_fr, Fr},
+        permutation::Permutation,
+    };
+
+    #[test]
+    fn test_generates_circomlib_constants() {
+        // circomlib's tables are this generator's output, optimized the same way.
+        let optimized =
+            ReferenceParameters::<Fr, T>::generate(SBOX_REFERENCE, 5, N_ROUNDS_F, N_ROUNDS_P)
+                .optimize();
+        let circomlib = CircomPoseidonConstants::shared();
+        assert_eq!(optimized.m, circomlib.m);
+        assert_eq!(optimized.c, circomlib.c);
+        assert_eq!(optimized.p, circomlib.p);
+        assert_eq!(optimized.s, circomlib.s);
+    }
+
+    #[test]
+    fn test_agrees_with() {
+        let circomlib = CircomPoseidonConstants::shared();
+        let reference =
+            ReferenceParameters::<Fr, T>::generate(SBOX_REFERENCE, 5, N_ROUNDS_F, N_ROUNDS_P);
+        assert!(reference.agrees_with(circomlib));
+        let other = ReferenceParameters::<Fr, T>::generate(SBOX_ZKHASH, 5, N_ROUNDS_F, N_ROUNDS_P);
+        assert!(!other.agrees_with(circomlib));
+    }
+
+    #[test]
+    fn test_cached() {
+        static CONSTANTS: OnceLock<OptimizedConstants<Fr, T>> = OnceLock::new();
+        let generate =
+            || ReferenceParameters::<Fr, T>::generate(SBOX_REFERENCE, 5, N_ROUNDS_F, N_ROUNDS_P);
+        let instance = cached(&CONSTANTS, generate);
+        assert_eq!(instance.c, &CircomPoseidonConstants::shared().c[..]);
+        // Later reads return the constants of the first.
+        let again = cached(&CONSTANTS, || unreachable!());
+        assert!(core::ptr::eq(instance.c, again.c));
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(hex::<Fr>("0x1"), Fr::from(1));
+        assert_eq!(hex::<Fr>("0x0100"), Fr::from(256));
+        assert_eq!(
+            hex::<Fr>("0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000"),
+            -Fr::from(1)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is not reduced")]
+    fn test_hex_rejects_the_modulus() {
+        hex::<Fr>("0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001");
+    }
+
+    #[test]
+    fn test_invert() {
+        let matrix = [
+            [Fr::from(2), Fr::from(3), Fr::from(5)],
+            [Fr::from(7), Fr::from(11), Fr::from(13)],
+            [Fr::from(17), Fr::from(19), Fr::from(23)],
+        ];
+        let inverse = invert(&matrix).unwrap();
+        assert_eq!(mul_matrix(&matrix, &inverse), identity());
+
+        let singular = [[Fr::from(1), Fr::from(2)], [Fr::from(2), Fr::from(4)]];
+        assert_eq!(invert(&singular), None);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn optimized_matches_reference(state in proptest::array::uniform3(arb_fr())) {
+            let reference = ReferenceParameters::<Fr, T>::generate(SBOX_REFERENCE, 5, N_ROUNDS_F, N_ROUNDS_P);
+            let mut optimized = state;
+            CircomPoseidonConstants::shared().permute(&mut optimized);
+            prop_assert_eq!(reference.permute(state), optimized);
+        }
+    }
+}
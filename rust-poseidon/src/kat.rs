@@ -0,0 +1,280 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::T,
+    field::{decimal, Fr},
+    functional::poseidon_hash_with_initial_state,
+    wtns::{self, WtnsError},
+};
+
+/// One known-answer vector: `width` is the state size `t`, i.e. one more than the arity.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vector {
+    pub width: usize,
+    #[serde(with = "decimal::vec")]
+    pub inputs: Vec<Fr>,
+    #[serde(with = "decimal", default)]
+    pub initial_state: Fr,
+    #[serde(with = "decimal")]
+    pub expected: Fr,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorFile {
+    pub vectors: Vec<Vector>,
+}
+
+impl VectorFile {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Outputs of another implementation, aligned with the vectors of a `VectorFile`.
+/// `None` marks a vector the implementation was not run on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Capture {
+    pub source: String,
+    pub outputs: Vec<Option<Fr>>,
+}
+
+impl Capture {
+    /// Reads what `poseidon.py --kat` prints: a JSON array of decimal strings or nulls.
+    /// The `Poseidon hash result: N` lines of the plain CLI are accepted too.
+    pub fn from_python_output(output: &str) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct Output(#[serde(with = "decimal")] Fr);
+
+        let outputs = match serde_json::from_str::<Vec<Option<Output>>>(output) {
+            Ok(outputs) => outputs
+                .into_iter()
+                .map(|output| output.map(|Output(value)| value))
+                .collect(),
+            Err(_) => output
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("Poseidon hash result:"))
+                .map(|value| match value.trim() {
+                    "unsupported" => Ok(None),
+                    value => decimal::from_str(value)
+                        .map(Some)
+                        .ok_or_else(|| format!("`{value}` is not a field element")),
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Capture {
+            source: "poseidon.py".to_string(),
+            outputs,
+        })
+    }
+
+    /// Collects the main output of one `generate_witness.js` witness per vector.
+    pub fn from_witnesses(witnesses: &[Option<&[u8]>]) -> Result<Self, (usize, WtnsError)> {
+        let outputs = witnesses
+            .iter()
+            .enumerate()
+            .map(|(index, bytes)| {
+                bytes
+                    .map(|bytes| {
+                        let witness = wtns::parse_wtns(bytes).map_err(|error| (index, error))?;
+                        witness
+                            .get(wtns::FIRST_OUTPUT)
+                            .copied()
+                            .ok_or((index, WtnsError::Truncated))
+                    })
+                    .transpose()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Capture {
+            source: "circom".to_string(),
+            outputs,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Disagreement {
+    pub width: usize,
+    pub vector: usize,
+    pub source: String,
+    pub expected: Fr,
+    pub actual: Fr,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "width {} vector {}: {} returned {}, expected {}",
+            self.width,
+            self.vector,
+            self.source,
+            decimal::to_string(&self.actual),
+            decimal::to_string(&self.expected)
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Number of (vector, implementation) pairs compared.
+    pub checked: usize,
+    /// Vectors whose width this crate does not implement, as `(width, vector)`.
+    pub unsupported: Vec<(usize, usize)>,
+    pub disagreements: Vec<Disagreement>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.disagreements.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} checks, {} disagreements",
+            self.checked,
+            self.disagreements.len()
+        )?;
+        for disagreement in &self.disagreements {
+            write!(f, "\n  {disagreement}")?;
+        }
+        for (width, vector) in &self.unsupported {
+            write!(
+                f,
+                "\n  width {width} vector {vector}: skipped, width not implemented"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every vector through `poseidon_hash` and compares the captured outputs of other
+/// implementations against the expected values.
+pub fn run(vectors: &VectorFile, captures: &[Capture]) -> Report {
+    let mut report = Report::default();
+    for (index, vector) in vectors.vectors.iter().enumerate() {
+        let mut check = |source: &str, actual: Fr| {
+            report.checked += 1;
+            if actual != vector.expected {
+                report.disagreements.push(Disagreement {
+                    width: vector.width,
+                    vector: index,
+                    source: source.to_string(),
+                    expected: vector.expected,
+                    actual,
+                });
+            }
+        };
+
+        match vector.inputs.as_slice() {
+            [x, y] if vector.width == T => check(
+                "rust",
+                poseidon_hash_with_initial_state(vector.initial_state, *x, *y),
+            ),
+            _ => report.unsupported.push((vector.width, index)),
+        }
+        for capture in captures {
+            if let Some(actual) = capture.outputs.get(index).copied().flatten() {
+                check(&capture.source, actual);
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VECTORS: &str = include_str!("../vectors/poseidon.json");
+
+    #[test]
+    fn test_kat_vectors() {
+        let vectors = VectorFile::from_json(VECTORS).unwrap();
+        let report = run(&vectors, &[]);
+
+        assert!(report.passed(), "{report}");
+        assert_eq!(report.checked, 8);
+        assert_eq!(report.unsupported, vec![(5, 8)]);
+    }
+
+    #[test]
+    fn test_kat_captures() {
+        let vectors = VectorFile::from_json(VECTORS).unwrap();
+        let expected: Vec<_> = vectors.vectors.iter().map(|v| v.expected).collect();
+
+        let python = Capture::from_python_output(&format!(
+            "Poseidon hash result: {}\nPoseidon hash result: 1\n",
+            decimal::to_string(&expected[0])
+        ))
+        .unwrap();
+        let circom_witness = wtns::write_wtns(&[Fr::from(1), expected[1]]);
+        let circom = Capture::from_witnesses(&[None, Some(&circom_witness)]).unwrap();
+
+        let report = run(&vectors, &[python, circom]);
+        assert_eq!(report.checked, 8 + 3);
+        assert_eq!(
+            report.disagreements,
+            vec![Disagreement {
+                width: 3,
+                vector: 1,
+                source: "poseidon.py".to_string(),
+                expected: expected[1],
+                actual: Fr::from(1),
+            }]
+        );
+        assert!(report
+            .to_string()
+            .contains("width 3 vector 1: poseidon.py returned 1, expected"));
+    }
+
+    #[test]
+    fn test_kat_python_json_output() {
+        let capture = Capture::from_python_output(r#"["1", null, "0x2"]"#).unwrap();
+        assert_eq!(
+            capture.outputs,
+            vec![Some(Fr::from(1)), None, Some(Fr::from(2))]
+        );
+    }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use super::*;
+        use crate::field::arb_fr;
+
+        fn arb_vector() -> impl Strategy<Value = Vector> {
+            (
+                2..8usize,
+                proptest::collection::vec(arb_fr(), 0..8),
+                arb_fr(),
+                arb_fr(),
+            )
+                .prop_map(|(width, inputs, initial_state, expected)| Vector {
+                    width,
+                    inputs,
+                    initial_state,
+                    expected,
+                })
+        }
+
+        proptest! {
+            #[test]
+            fn vector_file_round_trip(vectors in proptest::collection::vec(arb_vector(), 0..4)) {
+                let file = VectorFile { vectors };
+                let json = serde_json::to_string(&file).unwrap();
+                prop_assert_eq!(VectorFile::from_json(&json).unwrap(), file);
+            }
+
+            #[test]
+            fn python_output_never_panics(text in "\\PC{0,64}") {
+                let _ = Capture::from_python_output(&text);
+                let _ = VectorFile::from_json(&text);
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{
+    constants::{CircomPoseidonConstants, T},
+    field::Fr,
+    functional::hash_with,
+    mix::{self, MAX_TERMS},
+    mont::{self, Bn254},
+    permutation::Arithmetic,
+};
+
+/// Hashes `N` independent pairs at once; lane `l` equals `poseidon_hash(inputs[l][0], inputs[l][1])`.
+///
+/// The rounds are those of the single hash, run with `Lanes<N>` arithmetic.
+pub fn poseidon_hash_lanes<const N: usize>(
+    constants: &CircomPoseidonConstants,
+    inputs: &[[Fr; 2]; N],
+) -> [Fr; N] {
+    let state = [
+        [Fr::default(); N],
+        inputs.map(|[x, _]| x),
+        inputs.map(|[_, y]| y),
+    ];
+    hash_with::<Fr, T, Lanes<N>>(constants.parameters(), state)
+}
+
+/// BN254 arithmetic on `N` states at once: a lane holds element `i` of every state.
+///
+/// Every operation, the mix included, is applied to all states before the next one, so
+/// the multiplications of different hashes do not depend on each other. This is not
+/// faster than `N` single hashes, so `poseidon_hash_batch` and `MerkleTree` do not use it.
+pub struct Lanes<const N: usize>;
+
+impl<const N: usize> Arithmetic<Fr> for Lanes<N> {
+    type Elem = [Fr; N];
+
+    fn add(a: &[Fr; N], b: &[Fr; N]) -> [Fr; N] {
+        core::array::from_fn(|l| a[l] + b[l])
+    }
+
+    fn add_constant(a: &[Fr; N], c: &Fr) -> [Fr; N] {
+        a.map(|val| val + c)
+    }
+
+    fn mul(a: &[Fr; N], c: &Fr) -> [Fr; N] {
+        a.map(|val| mont::mul(&val, c))
+    }
+
+    fn sbox(x: &[Fr; N], alpha: u64) -> [Fr; N] {
+        if alpha != 5 {
+            return x.map(|val| Bn254::sbox(&val, alpha));
+        }
+        let squares = x.map(|val| mont::square(&val));
+        let fourths = squares.map(|val| mont::square(&val));
+        core::array::from_fn(|l| mont::mul(&x[l], &fourths[l]))
+    }
+
+    fn mix<const T: usize>(state: &[[Fr; N]; T], matrix: &[[Fr; T]; T]) -> [[Fr; N]; T] {
+        if T <= MAX_TERMS {
+            mix::mix_lanes(state, matrix)
+        } else {
+            let mixed: [[Fr; T]; N] =
+                core::array::from_fn(|l| Bn254::mix(&state.map(|element| element[l]), matrix));
+            core::array::from_fn(|i| core::array::from_fn(|l| mixed[l][i]))
+        }
+    }
+
+    fn mix_last<const T: usize>(state: &[[Fr; N]; T], matrix: &[[Fr; T]; T], s: usize) -> [Fr; N] {
+        core::array::from_fn(|l| Bn254::mix_last(&state.map(|element| element[l]), matrix, s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{field::arb_fr, functional::poseidon_hash};
+
+    fn check_lanes<const N: usize>(inputs: [[Fr; 2]; N]) -> Result<(), TestCaseError> {
+        let result = poseidon_hash_lanes(CircomPoseidonConstants::shared(), &inputs);
+        prop_assert_eq!(result, inputs.map(|[x, y]| poseidon_hash(x, y)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lanes_known_answer() {
+        let result = poseidon_hash_lanes(
+            CircomPoseidonConstants::shared(),
+            &[[Fr::from(1), Fr::from(2)], [Fr::from(2), Fr::from(1)]],
+        );
+        assert_eq!(
+            result,
+            [
+                poseidon_hash(Fr::from(1), Fr::from(2)),
+                poseidon_hash(Fr::from(2), Fr::from(1)),
+            ]
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn lanes_match_scalar(inputs in proptest::array::uniform8(proptest::array::uniform2(arb_fr()))) {
+            check_lanes::<1>([inputs[0]])?;
+            check_lanes::<4>(inputs[..4].try_into().unwrap())?;
+            check_lanes::<8>(inputs)?;
+        }
+    }
+}
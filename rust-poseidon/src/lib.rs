@@ -0,0 +1,45 @@
+//! Circomlib's Poseidon over BN254, and Poseidon instances over other prime fields.
+//!
+//! Without the default `std` feature the crate is `#![no_std]` but still needs `alloc`,
+//! which ark-ff links: a target must provide a global allocator.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+pub mod babybear;
+pub mod bls12_381;
+#[cfg(feature = "std")]
+pub mod cairo;
+#[cfg(feature = "std")]
+pub mod circomlib;
+#[cfg(feature = "std")]
+pub mod circuit;
+pub mod constants;
+pub mod field;
+pub mod functional;
+#[cfg(any(feature = "std", test))]
+pub mod generate;
+pub mod goldilocks;
+#[cfg(feature = "std")]
+pub mod kat;
+pub mod koalabear;
+pub mod lanes;
+#[cfg(feature = "std")]
+pub mod merkle;
+pub mod mersenne31;
+pub mod mix;
+pub mod mont;
+#[cfg(feature = "std")]
+pub mod neptune;
+pub mod packed;
+pub mod pasta;
+pub mod permutation;
+pub mod poseidon2;
+pub mod poseidon2_constants;
+pub mod small_field;
+pub mod sponge;
+pub mod starknet;
+#[cfg(feature = "std")]
+pub mod trace;
+#[cfg(feature = "std")]
+pub mod u384;
+#[cfg(feature = "std")]
+pub mod wtns;
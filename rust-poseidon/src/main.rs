@@ -1,12 +1,207 @@
-use field::Fr;
+use std::{env, fs, process::ExitCode};
 
-mod constants;
-mod field;
-mod functional;
+use rust_poseidon::{
+    cairo,
+    circuit::{self, Backend, CairoBackend, CircomBackend},
+    constants::CircomPoseidonConstants,
+    field::{decimal, Fr},
+    functional,
+    kat::{self, Capture, VectorFile},
+    trace::Trace,
+};
 
-fn main() {
-    println!("Hello, world!");
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => {
+            println!("Hello, world!");
 
-    let result = functional::poseidon_hash(Fr::from(1), Fr::from(2));
-    println!("result: {result}");
+            let result = functional::poseidon_hash(Fr::from(1), Fr::from(2));
+            println!("result: {result}");
+            ExitCode::SUCCESS
+        }
+        ["check-cairo", path] => check_cairo(path),
+        ["cairo-tests", rest @ ..] if rest.len() <= 2 => {
+            let random_cases = rest
+                .first()
+                .map_or(Ok(cairo::GENERATED_TESTS_RANDOM_CASES), |n| n.parse());
+            let seed = rest
+                .get(1)
+                .map_or(Ok(cairo::GENERATED_TESTS_SEED), |n| n.parse());
+            match (random_cases, seed) {
+                (Ok(random_cases), Ok(seed)) => {
+                    print!("{}", cairo::generate_test_module(random_cases, seed));
+                    ExitCode::SUCCESS
+                }
+                _ => usage(),
+            }
+        }
+        ["circuit", target] => emit_circuit(target),
+        ["trace", x, y] => match (decimal::from_str(x), decimal::from_str(y)) {
+            (Some(x), Some(y)) => {
+                println!("{}", functional::poseidon_hash_traced(x, y).1.to_json());
+                ExitCode::SUCCESS
+            }
+            _ => usage(),
+        },
+        ["trace-diff", ours, theirs] => trace_diff(ours, theirs),
+        ["kat", vectors, rest @ ..] => run_kat(vectors, rest),
+        ["kat-inputs", vectors, dir] => write_kat_inputs(vectors, dir),
+        _ => usage(),
+    }
+}
+
+fn emit_circuit(target: &str) -> ExitCode {
+    let circuit = circuit::poseidon_circuit(&CircomPoseidonConstants::default());
+    let backends: [&dyn Backend; 2] = [&CairoBackend::default(), &CircomBackend::default()];
+    if target == "stats" {
+        println!("ir: {}", circuit.stats());
+        for backend in backends {
+            println!("{}: {} gates", backend.name(), backend.gate_count(&circuit));
+        }
+        return ExitCode::SUCCESS;
+    }
+    match backends.iter().find(|backend| backend.name() == target) {
+        Some(backend) => {
+            print!("{}", backend.emit(&circuit));
+            ExitCode::SUCCESS
+        }
+        None => usage(),
+    }
+}
+
+fn trace_diff(ours: &str, theirs: &str) -> ExitCode {
+    let read = |path: &str| {
+        fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|json| Trace::from_json(&json).map_err(|error| error.to_string()))
+            .map_err(|error| eprintln!("{path}: {error}"))
+    };
+    let (Ok(ours), Ok(theirs)) = (read(ours), read(theirs)) else {
+        return ExitCode::FAILURE;
+    };
+    match ours.first_divergence(&theirs) {
+        None => {
+            println!("traces agree on all {} steps", ours.steps.len());
+            ExitCode::SUCCESS
+        }
+        Some((a, b)) => {
+            let describe = |step: Option<&rust_poseidon::trace::TraceStep>| match step {
+                Some(step) => format!(
+                    "round {} {:?}: [{}]",
+                    step.round,
+                    step.step,
+                    step.state
+                        .iter()
+                        .map(decimal::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                None => "missing".to_string(),
+            };
+            println!("first divergence");
+            println!("  ours:   {}", describe(a));
+            println!("  theirs: {}", describe(b));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn read_vectors(path: &str) -> Result<VectorFile, String> {
+    fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|json| VectorFile::from_json(&json).map_err(|error| error.to_string()))
+        .map_err(|error| format!("{path}: {error}"))
+}
+
+fn run_kat(vectors: &str, options: &[&str]) -> ExitCode {
+    let result = read_vectors(vectors).and_then(|vectors| {
+        let captures = options
+            .chunks(2)
+            .map(|option| match option {
+                ["--python", path] => fs::read_to_string(path)
+                    .map_err(|error| error.to_string())
+                    .and_then(|output| Capture::from_python_output(&output))
+                    .map_err(|error| format!("{path}: {error}")),
+                ["--wtns", dir] => {
+                    let witnesses: Vec<_> = (0..vectors.vectors.len())
+                        .map(|index| fs::read(format!("{dir}/{index}.wtns")).ok())
+                        .collect();
+                    let witnesses: Vec<_> = witnesses.iter().map(Option::as_deref).collect();
+                    Capture::from_witnesses(&witnesses)
+                        .map_err(|(index, error)| format!("{dir}/{index}.wtns: {error}"))
+                }
+                _ => Err("usage: kat <vectors.json> [--python <output>] [--wtns <dir>]".into()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(kat::run(&vectors, &captures))
+    });
+    match result {
+        Ok(report) => {
+            println!("{report}");
+            if report.passed() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Writes one `{ "inputs": [..] }` file per vector that circomlib's `Poseidon(2)` can run.
+fn write_kat_inputs(vectors: &str, dir: &str) -> ExitCode {
+    let result = read_vectors(vectors).and_then(|vectors| {
+        fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+        vectors
+            .vectors
+            .iter()
+            .enumerate()
+            .filter(|(_, vector)| vector.width == 3 && vector.initial_state == Fr::default())
+            .try_for_each(|(index, vector)| {
+                let inputs: Vec<_> = vector.inputs.iter().map(decimal::to_string).collect();
+                let json = serde_json::json!({ "inputs": inputs });
+                fs::write(format!("{dir}/{index}.input.json"), json.to_string())
+                    .map_err(|error| error.to_string())
+            })
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!("usage: rust-poseidon [check-cairo <lib.cairo> | cairo-tests [random-cases] [seed] | circuit <cairo|circom|stats> | trace <x> <y> | trace-diff <ours.json> <theirs.json> | kat <vectors.json> [--python <output>] [--wtns <dir>] | kat-inputs <vectors.json> <dir>]");
+    ExitCode::FAILURE
+}
+
+fn check_cairo(path: &str) -> ExitCode {
+    let result = fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|source| {
+            cairo::check_constant_table(&source, &CircomPoseidonConstants::default())
+                .map_err(|error| error.to_string())
+        });
+    match result {
+        Ok(()) => {
+            println!("{path}: {} matches", cairo::CONSTANT_TABLE_NAME);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{path}: {error}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,117 @@
+//! Binary Merkle trees over circomlib's width-3 `poseidon_hash`, with inclusion proofs.
+
+use ark_ff::AdditiveGroup;
+
+use crate::{
+    field::Fr,
+    functional::{poseidon_hash, poseidon_hash_batch},
+};
+
+/// A binary Merkle tree whose inner nodes are `poseidon_hash(left, right)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// `levels[0]` holds the leaves and the last level holds the root alone.
+    levels: Vec<Vec<Fr>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree, padding the leaves with zeros up to a power of two.
+    pub fn new(leaves: &[Fr]) -> Self {
+        let mut level = leaves.to_vec();
+        level.resize(leaves.len().max(1).next_power_of_two(), Fr::ZERO);
+
+        let mut levels = vec![level];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let (pairs, _) = level.as_chunks::<2>();
+            levels.push(poseidon_hash_batch(pairs));
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn root(&self) -> Fr {
+        self.levels[self.depth()][0]
+    }
+
+    /// Number of hashes between a leaf and the root.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// The padded leaves.
+    pub fn leaves(&self) -> &[Fr] {
+        &self.levels[0]
+    }
+
+    /// Siblings on the path from leaf `index` to the root, bottom first.
+    pub fn proof(&self, index: usize) -> Option<Vec<Fr>> {
+        if index >= self.leaves().len() {
+            return None;
+        }
+        let siblings = self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| level[(index >> height) ^ 1])
+            .collect();
+        Some(siblings)
+    }
+}
+
+/// Checks a proof produced by `MerkleTree::proof`.
+pub fn verify_proof(root: Fr, leaf: Fr, index: usize, siblings: &[Fr]) -> bool {
+    let node = siblings
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (height, sibling)| {
+            if (index >> height) & 1 == 0 {
+                poseidon_hash(node, *sibling)
+            } else {
+                poseidon_hash(*sibling, node)
+            }
+        });
+    index >> siblings.len() == 0 && node == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: u64) -> Vec<Fr> {
+        (1..=n).map(Fr::from).collect()
+    }
+
+    #[test]
+    fn test_merkle_root() {
+        let [a, b, c] = [Fr::from(1), Fr::from(2), Fr::from(3)];
+        assert_eq!(MerkleTree::new(&[a, b]).root(), poseidon_hash(a, b));
+
+        let padded = MerkleTree::new(&[a, b, c]);
+        assert_eq!(padded.depth(), 2);
+        assert_eq!(
+            padded.root(),
+            poseidon_hash(poseidon_hash(a, b), poseidon_hash(c, Fr::ZERO))
+        );
+
+        assert_eq!(MerkleTree::new(&[a]).root(), a);
+        assert_eq!(MerkleTree::new(&[]).root(), Fr::ZERO);
+    }
+
+    #[test]
+    fn test_merkle_proof() {
+        let tree = MerkleTree::new(&leaves(11));
+        assert_eq!(tree.depth(), 4);
+
+        for (index, leaf) in tree.leaves().iter().enumerate() {
+            let proof = tree.proof(index).unwrap();
+            assert_eq!(proof.len(), tree.depth());
+            assert!(verify_proof(tree.root(), *leaf, index, &proof));
+            assert!(!verify_proof(
+                tree.root(),
+                *leaf + Fr::from(1),
+                index,
+                &proof
+            ));
+            assert!(!verify_proof(tree.root(), *leaf, index + 16, &proof));
+        }
+        assert_eq!(tree.proof(16), None);
+    }
+}
@@ -0,0 +1,260 @@
+//! Poseidon2 over the Mersenne prime `2^31 - 1`, as Plonky3 instantiates it.
+//!
+//! `Mersenne31` is the field modulo `2^31 - 1` on the `small_field` backend, with a
+//! product reduced by folding its high bits onto its low bits. `p - 1` has a single factor
+//! of two, which Tonelli-Shanks handles like any other two-adicity. Its S-box is `x^5`.
+//! The instances are Plonky3's defaults, with 8 full rounds and 14 partial rounds at width
+//! 16 and 22 at width 24: round constants from the reference's Grain procedure,
+//! `M4::Plonky3` blocks, and internal diagonals of `-2` followed by powers of two.
+
+use crate::{
+    packed::{mersenne31_mul, Prime31},
+    poseidon2::{Poseidon2Parameters, M4},
+    small_field::{elements, rows, SmallField, SmallFp, SmallPrime},
+};
+
+/// `2^31 - 1`.
+pub const MODULUS: u32 = 0x7fff_ffff;
+
+pub struct Mersenne31Prime;
+
+impl SmallPrime for Mersenne31Prime {
+    const MODULUS: u64 = MODULUS as u64;
+    const GENERATOR: u64 = 7;
+    const TWO_ADICITY: u32 = 1;
+    const TWO_ADIC_ROOT_OF_UNITY: u64 = MODULUS as u64 - 1;
+
+    /// `2^31 = 1`, so the bits from 31 up fold onto the low ones. A product of canonical
+    /// values is below `2^62 - 2^32`, so the fold is below `2p` and one subtraction is
+    /// enough.
+    fn mul(a: u64, b: u64) -> u64 {
+        let product = a * b;
+        let folded = (product & Self::MODULUS) + (product >> 31);
+        if folded >= Self::MODULUS {
+            folded - Self::MODULUS
+        } else {
+            folded
+        }
+    }
+}
+
+impl Prime31 for Mersenne31Prime {
+    fn to_lane(value: u32) -> u32 {
+        value
+    }
+
+    fn from_lane(lane: u32) -> u32 {
+        lane
+    }
+
+    fn mul_lanes(a: u32, b: u32) -> u32 {
+        mersenne31_mul(a, b)
+    }
+}
+
+pub type Mersenne31Config = SmallField<Mersenne31Prime>;
+pub type Mersenne31 = SmallFp<Mersenne31Prime>;
+
+pub const ALPHA: u64 = 5;
+pub const ROUNDS_F: usize = 8;
+pub const ROUNDS_P_16: usize = 14;
+pub const ROUNDS_P_24: usize = 22;
+
+/// The width-16 constants of the full rounds, first and last four.
+#[rustfmt::skip]
+pub const EXTERNAL_16: [[u32; 16]; ROUNDS_F] = [
+    [
+        0x768bab52, 0x70e0ab7d, 0x3d266c8a, 0x6da42045, 0x600fef22, 0x41dace6b, 0x64f9bdd4, 0x5d42d4fe,
+        0x76b1516d, 0x6fc9a717, 0x70ac4fb6, 0x00194ef6, 0x22b644e2, 0x1f7916d5, 0x47581be2, 0x2710a123,
+    ],
+    [
+        0x6284e867, 0x018d3afe, 0x5df99ef3, 0x4c1e467b, 0x566f6abc, 0x2994e427, 0x538a6d42, 0x5d7bf2cf,
+        0x7fda2dab, 0x0fd854c4, 0x46922fca, 0x3d7763a1, 0x19fd05ca, 0x0a4bbb43, 0x15075851, 0x3d903d76,
+    ],
+    [
+        0x2d290ff7, 0x40809fa0, 0x59dac6ec, 0x127927a2, 0x6bbf0ea0, 0x0294140f, 0x24742976, 0x6e84c081,
+        0x22484f4a, 0x354cae59, 0x0453ffe1, 0x3f47a3cc, 0x0088204e, 0x6066e109, 0x3b7c4b80, 0x6b55665d,
+    ],
+    [
+        0x3bc4b897, 0x735bf378, 0x508daf42, 0x1884fc2b, 0x7214f24c, 0x7498be0a, 0x1a60e640, 0x3303f928,
+        0x29b46376, 0x5c96bb68, 0x65d097a5, 0x1d358e9f, 0x4a9a9017, 0x4724cf76, 0x347af70f, 0x1e77e59a,
+    ],
+    [
+        0x57090613, 0x1fa42108, 0x17bbef50, 0x1ff7e11c, 0x047b24ca, 0x4e140275, 0x4fa086f5, 0x079b309c,
+        0x1159bd47, 0x6d37e4e5, 0x075d8dce, 0x12121ca0, 0x7f6a7c40, 0x68e182ba, 0x5493201b, 0x0444a80e,
+    ],
+    [
+        0x0064f4c6, 0x6467abe6, 0x66975762, 0x2af68f9b, 0x345b33be, 0x1b70d47f, 0x053db717, 0x381189cb,
+        0x43b915f8, 0x20df3694, 0x0f459d26, 0x77a0e97b, 0x2f73e739, 0x1876c2f9, 0x65a0e29a, 0x4cabefbe,
+    ],
+    [
+        0x5abd1268, 0x4d34a760, 0x12771799, 0x69a0c9ac, 0x39091e55, 0x7f611cd0, 0x3af055da, 0x7ac0bbdf,
+        0x6e0f3a24, 0x41e3b6f7, 0x49b3756d, 0x568bc538, 0x20c079d8, 0x1701c72c, 0x7670dc6c, 0x5a439035,
+    ],
+    [
+        0x7c93e00e, 0x561fbb4d, 0x1178907b, 0x02737406, 0x32fb24f1, 0x6323b60a, 0x6ab12418, 0x42c99cea,
+        0x155a0b97, 0x53d1c6aa, 0x2bd20347, 0x279b3d73, 0x4f5f3c70, 0x0245af6c, 0x238359d3, 0x49966a59,
+    ],
+];
+
+/// The width-16 constants of the partial rounds.
+#[rustfmt::skip]
+pub const INTERNAL_16: [u32; ROUNDS_P_16] = [
+    0x7f7ec4bf, 0x0421926f, 0x5198e669, 0x34db3148, 0x4368bafd, 0x66685c7f, 0x78d3249a, 0x60187881,
+    0x76dad67a, 0x0690b437, 0x1ea95311, 0x40e5369a, 0x38f103fc, 0x1d226a21,
+];
+
+/// The width-16 internal diagonal, `-2` and then `2^s` for `s` in
+/// `[0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 12, 13, 14, 15, 16]`.
+#[rustfmt::skip]
+pub const DIAGONAL_16: [u32; 16] = [
+    0x7ffffffd, 0x00000001, 0x00000002, 0x00000004, 0x00000008, 0x00000010, 0x00000020, 0x00000040,
+    0x00000080, 0x00000100, 0x00000400, 0x00001000, 0x00002000, 0x00004000, 0x00008000, 0x00010000,
+];
+
+/// The width-24 constants of the full rounds, first and last four.
+#[rustfmt::skip]
+pub const EXTERNAL_24: [[u32; 24]; ROUNDS_F] = [
+    [
+        0x1feaba61, 0x53224454, 0x6bceb9e2, 0x5019f9b4, 0x48726592, 0x2b22d0a8, 0x6151bbf9, 0x2f474b21,
+        0x2eb5f337, 0x3b645d87, 0x0942cef0, 0x65228c52, 0x78ffb30f, 0x4d2837c8, 0x0e17ac4f, 0x05546686,
+        0x046c06cc, 0x0b51c3b6, 0x568db763, 0x38b334e4, 0x57f5acf0, 0x19d32611, 0x77d02f4b, 0x6c82e9b8,
+    ],
+    [
+        0x7148c1b6, 0x08067c75, 0x46d1e8c9, 0x30973b07, 0x20614f3b, 0x5c3ff851, 0x30503329, 0x4972e7cc,
+        0x02d1d8bc, 0x09d5bfa6, 0x097104c0, 0x7ba49a34, 0x4a07c2fc, 0x24c1ee69, 0x28a6ab41, 0x5d9108a0,
+        0x3a7851c7, 0x1dd495f9, 0x12b49ff4, 0x7bad5760, 0x5fed64c2, 0x66f5c96c, 0x7eafbd02, 0x39b3593b,
+    ],
+    [
+        0x4a653b49, 0x75091dc1, 0x56e488e0, 0x1704a355, 0x745e4ff3, 0x392ef16e, 0x31e33fdf, 0x02c28c66,
+        0x36c3083a, 0x3104d1fa, 0x5b03cda3, 0x6641e1af, 0x37754b56, 0x396f5af9, 0x1a1a461a, 0x688e26f2,
+        0x6f829784, 0x1bb91d69, 0x5b788016, 0x704aa5c5, 0x0181869c, 0x41211e56, 0x0ce803a0, 0x23bff3a0,
+    ],
+    [
+        0x17fb7064, 0x47317220, 0x76914b53, 0x219c1905, 0x16655528, 0x4df35544, 0x60808465, 0x3350f833,
+        0x03bccdc7, 0x0a87180a, 0x017a99f5, 0x6e945726, 0x15445504, 0x780533b1, 0x3b91bf38, 0x3fc77eb1,
+        0x4b4d960e, 0x3cd93d2e, 0x0ea4e976, 0x1d5306cc, 0x3a7ac284, 0x0ec22934, 0x4d979713, 0x51a41c65,
+    ],
+    [
+        0x1c662299, 0x057c955a, 0x7ab6c0f2, 0x25a6ad0a, 0x75850b58, 0x48fd3793, 0x0b4366b1, 0x0fdd0d49,
+        0x7db419f9, 0x49b9cc0f, 0x48949716, 0x29c35890, 0x76445485, 0x1c27d30c, 0x10aa7a3b, 0x30f34fb6,
+        0x6fe06435, 0x02135ecd, 0x6caaba96, 0x3eb290d0, 0x22fd8d3b, 0x768b1525, 0x5be95814, 0x523d7fe9,
+    ],
+    [
+        0x55e94cec, 0x47c42e1f, 0x1aa53b5e, 0x2fd1fe7e, 0x59230e91, 0x7472da66, 0x6443f2df, 0x2d9de19d,
+        0x6f7f6a84, 0x77800430, 0x0f014bc8, 0x7bf3d095, 0x26afd318, 0x582561f7, 0x5ee3198c, 0x6acc0000,
+        0x2f315e26, 0x27cac040, 0x2595081e, 0x5963b7da, 0x7e073565, 0x6cf3f5f1, 0x09f8a3a4, 0x0da8ccfe,
+    ],
+    [
+        0x60be2365, 0x7ed742f5, 0x668b8031, 0x4bb03494, 0x59019333, 0x700e2878, 0x1cc45856, 0x1d1617f7,
+        0x7b988da6, 0x4eb4936c, 0x78c9f87e, 0x63ce3e94, 0x7178341b, 0x45bc2f86, 0x05b775bc, 0x704b0244,
+        0x29eed278, 0x47f43032, 0x2127b2e5, 0x1997903f, 0x24b3ce03, 0x0c32298c, 0x7d2b6f3a, 0x17fcaa81,
+    ],
+    [
+        0x72f37fef, 0x3028e7a9, 0x5edd4d96, 0x1f96583b, 0x4cd6918a, 0x14880f0e, 0x69170359, 0x173cbd33,
+        0x0969e7f4, 0x6e7f23ab, 0x6182ea87, 0x4dcb1f5c, 0x585fa113, 0x729cb3b6, 0x01b3a27a, 0x1ba173e7,
+        0x4b33bcea, 0x63d93bbb, 0x6b3fbf99, 0x6f17e9d1, 0x0c3dd8ba, 0x0bc1f9a8, 0x64d3f370, 0x465a6a18,
+    ],
+];
+
+/// The width-24 constants of the partial rounds.
+#[rustfmt::skip]
+pub const INTERNAL_24: [u32; ROUNDS_P_24] = [
+    0x22776a11, 0x5fa34268, 0x1415528d, 0x563fbd14, 0x34f45244, 0x120ea1b6, 0x261368a5, 0x27665ec1,
+    0x36be2805, 0x345c4784, 0x17efdcc1, 0x393e6530, 0x6da0b4b8, 0x31e5ded3, 0x675b27ac, 0x0ae88c30,
+    0x577841cc, 0x5fe06dec, 0x56b0691a, 0x7242de1f, 0x3c377529, 0x339b7523,
+];
+
+/// The width-24 internal diagonal, `-2` and then `2^s` for `s` from 0 to 22.
+#[rustfmt::skip]
+pub const DIAGONAL_24: [u32; 24] = [
+    0x7ffffffd, 0x00000001, 0x00000002, 0x00000004, 0x00000008, 0x00000010, 0x00000020, 0x00000040,
+    0x00000080, 0x00000100, 0x00000200, 0x00000400, 0x00000800, 0x00001000, 0x00002000, 0x00004000,
+    0x00008000, 0x00010000, 0x00020000, 0x00040000, 0x00080000, 0x00100000, 0x00200000, 0x00400000,
+];
+
+/// Plonky3's `default_mersenne31_poseidon2_16`.
+pub fn poseidon2_16() -> Poseidon2Parameters<'static, Mersenne31, 16> {
+    static EXTERNAL: [[Mersenne31; 16]; ROUNDS_F] = rows(&EXTERNAL_16);
+    static INTERNAL: [Mersenne31; ROUNDS_P_16] = elements(&INTERNAL_16);
+    static DIAGONAL: [Mersenne31; 16] = elements(&DIAGONAL_16);
+    Poseidon2Parameters::new(
+        ALPHA,
+        ROUNDS_F,
+        ROUNDS_P_16,
+        &EXTERNAL,
+        &INTERNAL,
+        &DIAGONAL,
+    )
+    .with_m4(M4::Plonky3)
+}
+
+/// Plonky3's `default_mersenne31_poseidon2_24`.
+pub fn poseidon2_24() -> Poseidon2Parameters<'static, Mersenne31, 24> {
+    static EXTERNAL: [[Mersenne31; 24]; ROUNDS_F] = rows(&EXTERNAL_24);
+    static INTERNAL: [Mersenne31; ROUNDS_P_24] = elements(&INTERNAL_24);
+    static DIAGONAL: [Mersenne31; 24] = elements(&DIAGONAL_24);
+    Poseidon2Parameters::new(
+        ALPHA,
+        ROUNDS_F,
+        ROUNDS_P_24,
+        &EXTERNAL,
+        &INTERNAL,
+        &DIAGONAL,
+    )
+    .with_m4(M4::Plonky3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{permutation::Permutation, poseidon2::assert_grain_constants};
+
+    #[test]
+    fn test_generated_constants() {
+        assert_grain_constants(&poseidon2_16());
+        assert_grain_constants(&poseidon2_24());
+    }
+
+    #[test]
+    fn test_width_16() {
+        // Plonky3's `test_default_mersenne31_poseidon2_width_16`: the permutation of
+        // [0, 1, ..., 15].
+        let mut state = core::array::from_fn(|i| Mersenne31::from(i as u32));
+        poseidon2_16().permute(&mut state);
+        let expected: [u32; 16] = [
+            0x0b2c803a, 0x5b1ee4d1, 0x49c6b1e3, 0x2cdc280c, 0x310a60c8, 0x530a729e, 0x4e61bcb4,
+            0x2e84d3c3, 0x58709c08, 0x7e82ac42, 0x2162bcef, 0x6d153ab6, 0x742cf0e3, 0x2f21632d,
+            0x61adce1e, 0x1973d6f1,
+        ];
+        assert_eq!(state, expected.map(Mersenne31::from));
+    }
+
+    #[test]
+    fn test_width_24() {
+        // Plonky3's `test_default_mersenne31_poseidon2_width_24`: the permutation of
+        // [0, 1, ..., 23].
+        let mut state = core::array::from_fn(|i| Mersenne31::from(i as u32));
+        poseidon2_24().permute(&mut state);
+        let expected: [u32; 24] = [
+            0x2040f051, 0x7261dbfa, 0x4fbd519e, 0x2320ecaf, 0x039ef27c, 0x48d60ad5, 0x73ca17ff,
+            0x6023111a, 0x6c5e31e7, 0x373cd90d, 0x75a3ae11, 0x00ecc878, 0x33a7c097, 0x244c2171,
+            0x7552a38e, 0x58d20817, 0x00feecb7, 0x47c43c88, 0x30d3001c, 0x24d09ba6, 0x71f241d9,
+            0x1c72ab2e, 0x4749f79d, 0x61ff7579,
+        ];
+        assert_eq!(state, expected.map(Mersenne31::from));
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let diagonal = |shifts: &[u32]| -> Vec<Mersenne31> {
+            core::iter::once(-Mersenne31::from(2u32))
+                .chain(shifts.iter().map(|&s| Mersenne31::from(1u32 << s)))
+                .collect()
+        };
+        let shifts_16 = [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 12, 13, 14, 15, 16];
+        let shifts_24: Vec<u32> = (0..23).collect();
+        assert_eq!(elements(&DIAGONAL_16)[..], diagonal(&shifts_16)[..]);
+        assert_eq!(elements(&DIAGONAL_24)[..], diagonal(&shifts_24)[..]);
+    }
+}
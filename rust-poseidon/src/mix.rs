@@ -0,0 +1,260 @@
+//! BN254-specific MDS mixing with lazy reduction.
+//!
+//! `Fr` keeps values in Montgomery form `aR`, so the 512-bit product of two elements is
+//! `abR²`. A sum of such products only needs one Montgomery reduction to become the
+//! Montgomery form of the dot product, as long as it stays below `pR`. Products of
+//! canonical values are below `p²`, so `k` of them fit when `kp < R = 2^256`. BN254's
+//! modulus is about `0.189 · 2^256`, which allows five terms and covers every width-`T`
+//! row; the bound `p < 2^254` alone would only guarantee four.
+
+use ark_ff::{fields::MontConfig, BigInt, BigInteger};
+
+use crate::{
+    constants::T,
+    field::{Fr, FrConfig},
+};
+
+/// Largest number of products `dot` may accumulate before `p * terms` exceeds `R = 2^256`.
+pub const MAX_TERMS: usize = 5;
+
+const _: () = assert!(T <= MAX_TERMS);
+
+// `MAX_TERMS · p < 2^256 <= (MAX_TERMS + 1) · p`, checked on the top limb: the lower limbs
+// can add at most one to it.
+const _: () = {
+    let top = FrConfig::MODULUS.0[3] as u128;
+    assert!(MAX_TERMS as u128 * (top + 1) <= 1 << 64);
+    assert!((MAX_TERMS as u128 + 1) * top >= 1 << 64);
+};
+
+const MODULUS: [u64; 4] = FrConfig::MODULUS.0;
+
+/// `state · matrix`: output lane `j` is `sum_i state[i] * matrix[i][j]`, reduced once.
+///
+/// Panics if `N` exceeds `MAX_TERMS`.
+pub fn mix<const N: usize>(state: &[Fr; N], matrix: &[[Fr; N]; N]) -> [Fr; N] {
+    assert!(N <= MAX_TERMS, "mix of more than {MAX_TERMS} lanes");
+    let mut wide = [[0; 9]; N];
+    for (val, row) in state.iter().zip(matrix) {
+        for (acc, m) in wide.iter_mut().zip(row) {
+            mul_add(acc, val, m);
+        }
+    }
+    wide.map(|acc| reduce(&acc))
+}
+
+/// `mix` of `L` states at once, lane `i` of state `l` being `state[i][l]`.
+///
+/// Each state has its own accumulators, and the innermost loop runs over the states, so
+/// consecutive products go to different accumulators and do not wait on each other's
+/// carries.
+///
+/// Panics if `N` exceeds `MAX_TERMS`.
+pub fn mix_lanes<const N: usize, const L: usize>(
+    state: &[[Fr; L]; N],
+    matrix: &[[Fr; N]; N],
+) -> [[Fr; L]; N] {
+    assert!(N <= MAX_TERMS, "mix of more than {MAX_TERMS} lanes");
+    let mut wide = [[[0; 9]; L]; N];
+    for (vals, row) in state.iter().zip(matrix) {
+        for (accs, m) in wide.iter_mut().zip(row) {
+            for (acc, val) in accs.iter_mut().zip(vals) {
+                mul_add(acc, val, m);
+            }
+        }
+    }
+    wide.map(|accs| accs.map(|acc| reduce(&acc)))
+}
+
+/// `sum_i a[i] * b[i]` for at most `MAX_TERMS` products, reduced once.
+pub fn dot<'a>(pairs: impl IntoIterator<Item = (&'a Fr, &'a Fr)>) -> Fr {
+    let mut acc = [0; 9];
+    for (count, (a, b)) in pairs.into_iter().enumerate() {
+        assert!(
+            count < MAX_TERMS,
+            "dot product of more than {MAX_TERMS} terms"
+        );
+        mul_add(&mut acc, a, b);
+    }
+    reduce(&acc)
+}
+
+/// `acc += a * b` on the unreduced Montgomery representations.
+#[inline(always)]
+fn mul_add(acc: &mut [u64; 9], a: &Fr, b: &Fr) {
+    let (a, b) = (&a.0 .0, &b.0 .0);
+    for (i, a) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, b) in b.iter().enumerate() {
+            let t = acc[i + j] as u128 + *a as u128 * *b as u128 + carry;
+            acc[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        for limb in &mut acc[i + 4..] {
+            let t = *limb as u128 + carry;
+            *limb = t as u64;
+            carry = t >> 64;
+            if carry == 0 {
+                break;
+            }
+        }
+    }
+}
+
+/// Montgomery reduction of `acc < pR` to the canonical `acc / R mod p`.
+#[inline(always)]
+fn reduce(acc: &[u64; 9]) -> Fr {
+    let mut t = *acc;
+    for i in 0..4 {
+        let m = t[i].wrapping_mul(FrConfig::INV);
+        let mut carry = 0;
+        for (j, p) in MODULUS.iter().enumerate() {
+            let s = t[i + j] as u128 + m as u128 * *p as u128 + carry;
+            t[i + j] = s as u64;
+            carry = s >> 64;
+        }
+        for limb in &mut t[i + 4..] {
+            let s = *limb as u128 + carry;
+            *limb = s as u64;
+            carry = s >> 64;
+            if carry == 0 {
+                break;
+            }
+        }
+    }
+    debug_assert_eq!(t[8], 0, "lazy accumulator exceeded pR");
+
+    // The result is below 2p; one conditional subtraction makes it canonical.
+    let mut result = BigInt([t[4], t[5], t[6], t[7]]);
+    if result >= FrConfig::MODULUS {
+        result.sub_with_borrow(&FrConfig::MODULUS);
+    }
+    Fr::new_unchecked(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{AdditiveGroup, Field, PrimeField};
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{constants::CircomPoseidonConstants, field::arb_fr};
+
+    fn reference_mix<const N: usize>(state: &[Fr; N], matrix: &[[Fr; N]; N]) -> [Fr; N] {
+        let mut result = [Fr::ZERO; N];
+        for (val, row) in state.iter().zip(matrix) {
+            for (acc, m) in result.iter_mut().zip(row) {
+                *acc += *val * m;
+            }
+        }
+        result
+    }
+
+    /// Values at the edges of the Montgomery representation, where a missed carry or a
+    /// missing final subtraction would show up.
+    fn edge_values() -> Vec<Fr> {
+        let r = Fr::from_bigint(FrConfig::R).unwrap();
+        vec![
+            Fr::ZERO,
+            Fr::ONE,
+            Fr::from(2),
+            -Fr::ONE,
+            -Fr::from(2),
+            Fr::from(u64::MAX),
+            -Fr::from(2).inverse().unwrap(),
+            r,
+            -r,
+            Fr::new_unchecked(BigInt([u64::MAX, u64::MAX, u64::MAX, 0])),
+        ]
+    }
+
+    #[test]
+    fn test_mix_edge_values() {
+        let constants = CircomPoseidonConstants::shared();
+        let edges = edge_values();
+        let matrices = [
+            constants.m,
+            constants.p,
+            [[-Fr::ONE; T]; T],
+            [[edges[9]; T]; T],
+        ];
+
+        for &a in &edges {
+            for &b in &edges {
+                for &c in &edges {
+                    let state = [a, b, c];
+                    for matrix in &matrices {
+                        assert_eq!(mix(&state, matrix), reference_mix(&state, matrix));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dot_max_terms() {
+        let worst = [-Fr::ONE; MAX_TERMS];
+        assert_eq!(dot(worst.iter().zip(&worst)), Fr::from(MAX_TERMS as u64));
+        assert_eq!(dot([]), Fr::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "more than 5 lanes")]
+    fn test_mix_too_many_lanes() {
+        mix(
+            &[Fr::ONE; MAX_TERMS + 1],
+            &[[Fr::ONE; MAX_TERMS + 1]; MAX_TERMS + 1],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "more than 5 terms")]
+    fn test_dot_too_many_terms() {
+        let values = [Fr::ONE; MAX_TERMS + 1];
+        dot(values.iter().zip(&values));
+    }
+
+    proptest! {
+        #[test]
+        fn mix_matches_reference(
+            state in proptest::array::uniform3(arb_fr()),
+            matrix in proptest::array::uniform3(proptest::array::uniform3(arb_fr())),
+        ) {
+            prop_assert_eq!(mix(&state, &matrix), reference_mix(&state, &matrix));
+        }
+
+        #[test]
+        fn mix_widths_match_reference(
+            state in proptest::array::uniform5(arb_fr()),
+            matrix in proptest::array::uniform5(proptest::array::uniform5(arb_fr())),
+        ) {
+            prop_assert_eq!(mix(&state, &matrix), reference_mix(&state, &matrix));
+            let [a, b, ..] = state;
+            let [[m00, m01, ..], [m10, m11, ..], ..] = matrix;
+            let (state, matrix) = ([a, b], [[m00, m01], [m10, m11]]);
+            prop_assert_eq!(mix(&state, &matrix), reference_mix(&state, &matrix));
+        }
+
+        #[test]
+        fn mix_lanes_matches_reference(
+            states in proptest::array::uniform4(proptest::array::uniform3(arb_fr())),
+            matrix in proptest::array::uniform3(proptest::array::uniform3(arb_fr())),
+        ) {
+            let lanes: [[Fr; 4]; 3] = core::array::from_fn(|i| states.map(|state| state[i]));
+            let mixed = mix_lanes(&lanes, &matrix);
+            for (l, state) in states.iter().enumerate() {
+                let expected = reference_mix(state, &matrix);
+                prop_assert_eq!(mixed.map(|lane| lane[l]), expected);
+            }
+        }
+
+        #[test]
+        fn dot_matches_reference(
+            a in proptest::collection::vec(arb_fr(), 0..=MAX_TERMS),
+            b in proptest::collection::vec(arb_fr(), MAX_TERMS),
+        ) {
+            let expected = a.iter().zip(&b).fold(Fr::ZERO, |acc, (a, b)| acc + *a * b);
+            prop_assert_eq!(dot(a.iter().zip(&b)), expected);
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! BN254 scalar multiplication, squaring and `x^5` for the S-box.
+//!
+//! With the `asm` feature on x86-64, multiplication runs a Montgomery routine built on
+//! MULX/ADCX/ADOX whenever the CPU reports BMI2 and ADX at run time. Everywhere else it
+//! falls back to ark-ff's portable arithmetic. Both paths give identical results.
+//! Without the `std` feature the CPU check happens at compile time instead.
+
+use ark_ff::Field;
+
+use crate::{
+    field::Fr,
+    mix::{self, MAX_TERMS},
+    permutation::{Arithmetic, Ark},
+};
+
+pub fn mul(a: &Fr, b: &Fr) -> Fr {
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    if x86_64::has_mulx_adx() {
+        // SAFETY: BMI2 and ADX were detected on this CPU.
+        return unsafe { x86_64::mul(a, b) };
+    }
+    *a * b
+}
+
+pub fn square(a: &Fr) -> Fr {
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    if x86_64::has_mulx_adx() {
+        // SAFETY: BMI2 and ADX were detected on this CPU.
+        return unsafe { x86_64::mul(a, a) };
+    }
+    a.square()
+}
+
+/// `x^5` as two squarings and a multiplication, instead of `pow`'s bit loop.
+pub fn pow5(x: &Fr) -> Fr {
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    if x86_64::has_mulx_adx() {
+        // SAFETY: BMI2 and ADX were detected on this CPU.
+        unsafe {
+            let x2 = x86_64::mul(x, x);
+            return x86_64::mul(&x86_64::mul(&x2, &x2), x);
+        }
+    }
+    x.square().square() * x
+}
+
+/// The functions above and `mix.rs`'s lazy reduction, as the permutation's arithmetic.
+pub struct Bn254;
+
+impl Arithmetic<Fr> for Bn254 {
+    type Elem = Fr;
+
+    fn add(a: &Fr, b: &Fr) -> Fr {
+        *a + b
+    }
+
+    fn add_constant(a: &Fr, c: &Fr) -> Fr {
+        *a + c
+    }
+
+    fn mul(a: &Fr, c: &Fr) -> Fr {
+        mul(a, c)
+    }
+
+    fn sbox(x: &Fr, alpha: u64) -> Fr {
+        if alpha == 5 {
+            pow5(x)
+        } else {
+            Ark::sbox(x, alpha)
+        }
+    }
+
+    fn mix<const T: usize>(state: &[Fr; T], matrix: &[[Fr; T]; T]) -> [Fr; T] {
+        if T <= MAX_TERMS {
+            mix::mix(state, matrix)
+        } else {
+            Ark::mix(state, matrix)
+        }
+    }
+
+    fn mix_last<const T: usize>(state: &[Fr; T], matrix: &[[Fr; T]; T], s: usize) -> Fr {
+        if T <= MAX_TERMS {
+            mix::dot(state.iter().zip(matrix.iter().map(|row| &row[s])))
+        } else {
+            Ark::mix_last(state, matrix, s)
+        }
+    }
+}
+
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+mod x86_64 {
+    use core::arch::asm;
+
+    use ark_ff::{fields::MontConfig, BigInt, BigInteger};
+
+    use crate::field::{Fr, FrConfig};
+
+    /// The modulus limbs followed by `-p^-1 mod 2^64`, addressed from the assembly.
+    static MODULUS_AND_INV: [u64; 5] = {
+        let [p0, p1, p2, p3] = FrConfig::MODULUS.0;
+        [p0, p1, p2, p3, FrConfig::INV]
+    };
+
+    /// Run-time detection needs std; without it, only a build for a CPU with BMI2 and ADX
+    /// (e.g. `-C target-cpu=native`) takes the assembly path.
+    pub(super) fn has_mulx_adx() -> bool {
+        #[cfg(feature = "std")]
+        return std::is_x86_feature_detected!("bmi2") && std::is_x86_feature_detected!("adx");
+        #[cfg(not(feature = "std"))]
+        return cfg!(all(target_feature = "bmi2", target_feature = "adx"));
+    }
+
+    /// CIOS Montgomery multiplication. MULX keeps the flags intact, so the low and
+    /// high halves of each row are summed on two independent carry chains: ADCX on CF
+    /// and ADOX on OF. BN254's modulus leaves the top two bits free, so the running
+    /// value never needs a sixth limb.
+    ///
+    /// # Safety
+    ///
+    /// The CPU must support BMI2 and ADX.
+    pub(super) unsafe fn mul(a: &Fr, b: &Fr) -> Fr {
+        let (a, b) = (&a.0 .0, &b.0 .0);
+        let [mut t0, mut t1, mut t2, mut t3]: [u64; 4];
+        asm!(
+            // t = a * b[0]
+            "xor {lo:e}, {lo:e}",
+            "mov rdx, qword ptr [{b}]",
+            "mulx {t1}, {t0}, qword ptr [{a}]",
+            "mulx {t2}, {lo}, qword ptr [{a} + 8]",
+            "adox {t1}, {lo}",
+            "mulx {t3}, {lo}, qword ptr [{a} + 16]",
+            "adox {t2}, {lo}",
+            "mulx {t4}, {lo}, qword ptr [{a} + 24]",
+            "adox {t3}, {lo}",
+            "mov {lo:e}, 0",
+            "adox {t4}, {lo}",
+            // t = (t + m * p) / 2^64 with m = t0 * inv
+            "mov rdx, qword ptr [{p} + 32]",
+            "imul rdx, {t0}",
+            "xor {lo:e}, {lo:e}",
+            "mulx {hi}, {lo}, qword ptr [{p}]",
+            "adcx {lo}, {t0}",
+            "mov {t0}, {hi}",
+            "adcx {t0}, {t1}",
+            "mulx {t1}, {lo}, qword ptr [{p} + 8]",
+            "adox {t0}, {lo}",
+            "adcx {t1}, {t2}",
+            "mulx {t2}, {lo}, qword ptr [{p} + 16]",
+            "adox {t1}, {lo}",
+            "adcx {t2}, {t3}",
+            "mulx {t3}, {lo}, qword ptr [{p} + 24]",
+            "adox {t2}, {lo}",
+            "mov {lo:e}, 0",
+            "adcx {t3}, {lo}",
+            "adox {t3}, {t4}",
+            // t += a * b[1], reduce
+            "xor {lo:e}, {lo:e}",
+            "mov rdx, qword ptr [{b} + 8]",
+            "mulx {hi}, {lo}, qword ptr [{a}]",
+            "adox {t0}, {lo}",
+            "adcx {t1}, {hi}",
+            "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+            "adox {t1}, {lo}",
+            "adcx {t2}, {hi}",
+            "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+            "adox {t2}, {lo}",
+            "adcx {t3}, {hi}",
+            "mulx {t4}, {lo}, qword ptr [{a} + 24]",
+            "adox {t3}, {lo}",
+            "mov {lo:e}, 0",
+            "adcx {t4}, {lo}",
+            "adox {t4}, {lo}",
+            "mov rdx, qword ptr [{p} + 32]",
+            "imul rdx, {t0}",
+            "xor {lo:e}, {lo:e}",
+            "mulx {hi}, {lo}, qword ptr [{p}]",
+            "adcx {lo}, {t0}",
+            "mov {t0}, {hi}",
+            "adcx {t0}, {t1}",
+            "mulx {t1}, {lo}, qword ptr [{p} + 8]",
+            "adox {t0}, {lo}",
+            "adcx {t1}, {t2}",
+            "mulx {t2}, {lo}, qword ptr [{p} + 16]",
+            "adox {t1}, {lo}",
+            "adcx {t2}, {t3}",
+            "mulx {t3}, {lo}, qword ptr [{p} + 24]",
+            "adox {t2}, {lo}",
+            "mov {lo:e}, 0",
+            "adcx {t3}, {lo}",
+            "adox {t3}, {t4}",
+            // t += a * b[2], reduce
+            "xor {lo:e}, {lo:e}",
+            "mov rdx, qword ptr [{b} + 16]",
+            "mulx {hi}, {lo}, qword ptr [{a}]",
+            "adox {t0}, {lo}",
+            "adcx {t1}, {hi}",
+            "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+            "adox {t1}, {lo}",
+            "adcx {t2}, {hi}",
+            "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+            "adox {t2}, {lo}",
+            "adcx {t3}, {hi}",
+            "mulx {t4}, {lo}, qword ptr [{a} + 24]",
+            "adox {t3}, {lo}",
+            "mov {lo:e}, 0",
+            "adcx {t4}, {lo}",
+            "adox {t4}, {lo}",
+            "mov rdx, qword ptr [{p} + 32]",
+            "imul rdx, {t0}",
+            "xor {lo:e}, {lo:e}",
+            "mulx {hi}, {lo}, qword ptr [{p}]",
+            "adcx {lo}, {t0}",
+            "mov {t0}, {hi}",
+            "adcx {t0}, {t1}",
+            "mulx {t1}, {lo}, qword ptr [{p} + 8]",
+            "adox {t0}, {lo}",
+            "adcx {t1}, {t2}",
+            "mulx {t2}, {lo}, qword ptr [{p} + 16]",
+            "adox {t1}, {lo}",
+            "adcx {t2}, {t3}",
+            "mulx {t3}, {lo}, qword ptr [{p} + 24]",
+            "adox {t2}, {lo}",
+            "mov {lo:e}, 0",
+            "adcx {t3}, {lo}",
+            "adox {t3}, {t4}",
+            // t += a * b[3], reduce
+            "xor {lo:e}, {lo:e}",
+            "mov rdx, qword ptr [{b} + 24]",
+            "mulx {hi}, {lo}, qword ptr [{a}]",
+            "adox {t0}, {lo}",
+            "adcx {t1}, {hi}",
+            "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+            "adox {t1}, {lo}",
+            "adcx {t2}, {hi}",
+            "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+            "adox {t2}, {lo}",
+            "adcx {t3}, {hi}",
+            "mulx {t4}, {lo}, qword ptr [{a} + 24]",
+            "adox {t3}, {lo}",
+            "mov {lo:e}, 0",
+            "adcx {t4}, {lo}",
+            "adox {t4}, {lo}",
+            "mov rdx, qword ptr [{p} + 32]",
+            "imul rdx, {t0}",
+            "xor {lo:e}, {lo:e}",
+            "mulx {hi}, {lo}, qword ptr [{p}]",
+            "adcx {lo}, {t0}",
+            "mov {t0}, {hi}",
+            "adcx {t0}, {t1}",
+            "mulx {t1}, {lo}, qword ptr [{p} + 8]",
+            "adox {t0}, {lo}",
+            "adcx {t1}, {t2}",
+            "mulx {t2}, {lo}, qword ptr [{p} + 16]",
+            "adox {t1}, {lo}",
+            "adcx {t2}, {t3}",
+            "mulx {t3}, {lo}, qword ptr [{p} + 24]",
+            "adox {t2}, {lo}",
+            "mov {lo:e}, 0",
+            "adcx {t3}, {lo}",
+            "adox {t3}, {t4}",
+            a = in(reg) a.as_ptr(),
+            b = in(reg) b.as_ptr(),
+            p = in(reg) MODULUS_AND_INV.as_ptr(),
+            t0 = out(reg) t0,
+            t1 = out(reg) t1,
+            t2 = out(reg) t2,
+            t3 = out(reg) t3,
+            t4 = out(reg) _,
+            lo = out(reg) _,
+            hi = out(reg) _,
+            out("rdx") _,
+            options(pure, readonly, nostack),
+        );
+
+        // The result is below 2p; one conditional subtraction makes it canonical.
+        let mut result = BigInt([t0, t1, t2, t3]);
+        if result >= FrConfig::MODULUS {
+            result.sub_with_borrow(&FrConfig::MODULUS);
+        }
+        Fr::new_unchecked(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{AdditiveGroup, BigInt, PrimeField};
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::field::{arb_fr, FrConfig};
+
+    fn edge_values() -> Vec<Fr> {
+        use ark_ff::fields::MontConfig;
+
+        let r = Fr::from_bigint(FrConfig::R).unwrap();
+        vec![
+            Fr::ZERO,
+            Fr::ONE,
+            -Fr::ONE,
+            -Fr::from(2),
+            Fr::from(u64::MAX),
+            r,
+            -r,
+            Fr::new_unchecked(BigInt([u64::MAX, u64::MAX, u64::MAX, 0])),
+            Fr::new_unchecked(BigInt([0, 0, 0, 1 << 61])),
+        ]
+    }
+
+    #[test]
+    fn test_mont_edge_values() {
+        let edges = edge_values();
+        for a in &edges {
+            assert_eq!(square(a), *a * a);
+            assert_eq!(pow5(a), a.pow([5]));
+            for b in &edges {
+                assert_eq!(mul(a, b), *a * b);
+            }
+        }
+    }
+
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    #[test]
+    fn test_mulx_matches_portable() {
+        // Calls the assembly directly, so a dispatch bug cannot hide it behind ark-ff.
+        if !x86_64::has_mulx_adx() {
+            return;
+        }
+        let edges = edge_values();
+        for a in &edges {
+            for b in &edges {
+                // SAFETY: BMI2 and ADX were detected above.
+                assert_eq!(unsafe { x86_64::mul(a, b) }, *a * b);
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1024))]
+
+        #[test]
+        fn mul_matches_ark(a in arb_fr(), b in arb_fr()) {
+            prop_assert_eq!(mul(&a, &b), a * b);
+            prop_assert_eq!(square(&a), a * a);
+        }
+
+        #[test]
+        fn pow5_matches_ark(x in arb_fr()) {
+            prop_assert_eq!(pow5(&x), x.pow([5]));
+        }
+    }
+}
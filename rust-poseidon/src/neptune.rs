@@ -0,0 +1,441 @@
+//! Poseidon as Filecoin's neptune computes it, over the BLS12-381 scalar field.
+//!
+//! neptune draws its constants like the reference implementation but chooses its own
+//! instances: the round numbers come from its port of the reference's round-number script
+//! (with a security margin, and 25% more partial rounds when `Strengthened`), and the
+//! MDS matrix is the fixed Cauchy matrix `1 / (i + j + T)` instead of a drawn one. An
+//! arity-`k` hash permutes `[tag, x_1, ..., x_k]` and returns lane 1, where the tag
+//! separates the hash types (`HashType`).
+//!
+//! The instances for the arities Filecoin uses, 2, 4, 8 and 11, are `poseidon_3`,
+//! `poseidon_5`, `poseidon_9` and `poseidon_12`. Their constants are generated on first
+//! use.
+
+use std::sync::OnceLock;
+
+use ark_ff::{AdditiveGroup, Field, PrimeField};
+
+use crate::{
+    bls12_381::Fr,
+    generate::{cached, Grain, OptimizedConstants, ReferenceParameters, SBOX_ZKHASH},
+    permutation::{Permutation, PoseidonParameters},
+    sponge::Sponge,
+};
+
+pub const ALPHA: u64 = 5;
+/// The field size its round-number script assumes, rounded up from BLS12-381's 255 bits.
+const PRIME_BITS: f32 = 256.0;
+/// The security level, in bits.
+const SECURITY: f32 = 128.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strength {
+    Standard,
+    /// 25% more partial rounds, in case of new attacks.
+    Strengthened,
+}
+
+/// `(R_F, R_P)` for a width-`t` instance, as neptune chooses them: the cheapest secure
+/// pair, counted in S-boxes, plus 2 full rounds and 7.5% more partial rounds.
+pub fn round_numbers(t: usize, strength: Strength) -> (usize, usize) {
+    let (mut rounds_f, mut rounds_p) = (0, 0);
+    let mut min_sboxes = usize::MAX;
+    for f in (2..=1000).step_by(2) {
+        for p in 4..200 {
+            if !is_secure(t, f, p) {
+                continue;
+            }
+            let (f, p) = (f + 2, (1.075 * p as f32).ceil() as usize);
+            let sboxes = t * f + p;
+            if sboxes < min_sboxes || (sboxes == min_sboxes && f < rounds_f) {
+                (rounds_f, rounds_p, min_sboxes) = (f, p, sboxes);
+            }
+        }
+    }
+    match strength {
+        Strength::Standard => (rounds_f, rounds_p),
+        Strength::Strengthened => (rounds_f, (rounds_p as f64 * 1.25).ceil() as usize),
+    }
+}
+
+/// The paper's bounds on `R_F`, in the single precision neptune evaluates them in.
+fn is_secure(t: usize, rounds_f: usize, rounds_p: usize) -> bool {
+    let (t, rounds_p, n, m) = (t as f32, rounds_p as f32, PRIME_BITS, SECURITY);
+    let statistical = if m <= (n - 3.0) * (t + 1.0) {
+        6.0
+    } else {
+        10.0
+    };
+    let interpolation = 0.43 * m + t.log2() - rounds_p;
+    let groebner_1 = 0.21 * n - rounds_p;
+    let groebner_2 = (0.14 * n - 1.0 - rounds_p) / (t - 1.0);
+    [statistical, interpolation, groebner_1, groebner_2]
+        .iter()
+        .all(|bound| rounds_f >= bound.ceil() as usize)
+}
+
+impl<const T: usize> ReferenceParameters<Fr, T> {
+    /// neptune's instance of width `T`: its round numbers, the round constants from the
+    /// Grain stream, and the Cauchy matrix on `x_i = i`, `y_j = T + j`.
+    pub fn neptune(strength: Strength) -> Self {
+        let (rounds_f, rounds_p) = round_numbers(T, strength);
+        let mut grain = Grain::new(SBOX_ZKHASH, Fr::MODULUS_BIT_SIZE, T, rounds_f, rounds_p);
+        let round_constants = (0..rounds_f + rounds_p)
+            .map(|_| core::array::from_fn(|_| grain.next_field_element()))
+            .collect();
+        let mds = core::array::from_fn(|i| {
+            core::array::from_fn(|j| {
+                let denominator = Fr::from((i + T + j) as u64);
+                denominator
+                    .inverse()
+                    .expect("the Cauchy entries are nonzero")
+            })
+        });
+        ReferenceParameters {
+            alpha: ALPHA,
+            rounds_f,
+            rounds_p,
+            round_constants,
+            mds,
+        }
+    }
+}
+
+macro_rules! instance {
+    ($name:ident, $t:literal) => {
+        #[doc = concat!("The width-", $t, " instance, for arity ", $t, " - 1.")]
+        pub fn $name(strength: Strength) -> PoseidonParameters<'static, Fr, $t> {
+            static CONSTANTS: [OnceLock<OptimizedConstants<Fr, $t>>; 2] =
+                [OnceLock::new(), OnceLock::new()];
+            cached(&CONSTANTS[strength as usize], || {
+                ReferenceParameters::neptune(strength)
+            })
+        }
+    };
+}
+
+instance!(poseidon_3, 3);
+instance!(poseidon_5, 5);
+instance!(poseidon_9, 9);
+instance!(poseidon_12, 12);
+
+/// The hash types neptune supports, told apart by the tag in lane 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashType {
+    /// An inner node with one child per input. Tagged `2^arity - 1`.
+    MerkleTree,
+    /// A message of this many elements, zero-padded to the arity. Tagged `length · 2^64`.
+    ConstantLength(usize),
+    /// Tagged `2^32`; inputs are zero-padded to the arity.
+    Encryption,
+    /// An application's own type, from 1 to 256. Tagged `id · 2^40`.
+    Custom(u64),
+}
+
+impl HashType {
+    /// Panics for a `Custom` id outside `1..=256`.
+    pub fn domain_tag<F: Field>(&self, arity: usize) -> F {
+        let two = F::from(2u64);
+        match *self {
+            HashType::MerkleTree => two.pow([arity as u64]) - F::ONE,
+            HashType::ConstantLength(length) => F::from(length as u64) * two.pow([64]),
+            HashType::Encryption => two.pow([32]),
+            HashType::Custom(id) => {
+                assert!((1..=256).contains(&id), "custom domain tag id out of range");
+                F::from(id) * two.pow([40])
+            }
+        }
+    }
+}
+
+/// Hashes `inputs` with a width-`T` instance, i.e. arity `T - 1`, returning lane 1.
+///
+/// Panics unless `MerkleTree` and `Custom` get exactly `T - 1` inputs, `ConstantLength`
+/// gets its length of at most `T - 1`, and `Encryption` at most `T - 1`. Longer
+/// constant-length messages go through `sponge`.
+pub fn hash<P: Permutation<T>, const T: usize>(
+    permutation: P,
+    hash_type: HashType,
+    inputs: &[P::Field],
+) -> P::Field {
+    let arity = T - 1;
+    match hash_type {
+        HashType::MerkleTree | HashType::Custom(_) => {
+            assert_eq!(
+                inputs.len(),
+                arity,
+                "preimage size does not fit the hash type"
+            );
+        }
+        HashType::ConstantLength(length) => {
+            assert_eq!(
+                inputs.len(),
+                length,
+                "preimage size does not fit the hash type"
+            );
+            assert!(length <= arity, "longer messages need a sponge");
+        }
+        HashType::Encryption => assert!(
+            inputs.len() <= arity,
+            "preimage size does not fit the hash type"
+        ),
+    }
+    let mut state = [P::Field::ZERO; T];
+    state[0] = hash_type.domain_tag(arity);
+    state[1..=inputs.len()].copy_from_slice(inputs);
+    permutation.permute(&mut state);
+    state[1]
+}
+
+/// neptune's simplex sponge for a `length`-element message: rate `T - 1`, with the
+/// `ConstantLength(length)` tag as its capacity. Absorb exactly `length` elements before
+/// squeezing; the digest is the first element squeezed.
+pub fn sponge<P: Permutation<T>, const T: usize>(permutation: P, length: usize) -> Sponge<P, T> {
+    let tag = HashType::ConstantLength(length).domain_tag(T - 1);
+    Sponge::new(permutation, T - 1, tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{BigInt, PrimeField};
+
+    use super::*;
+
+    /// neptune's `scalar_from_u64s`: little-endian limbs.
+    fn scalar(limbs: [u64; 4]) -> Fr {
+        Fr::from_bigint(BigInt(limbs)).unwrap()
+    }
+
+    fn count<const N: usize>() -> [Fr; N] {
+        core::array::from_fn(|i| Fr::from(i as u64))
+    }
+
+    #[test]
+    fn test_round_numbers() {
+        // neptune's table of `t` and `R_P`; `R_F` is always 8.
+        let cases = [
+            (2, 55),
+            (3, 55),
+            (4, 56),
+            (5, 56),
+            (8, 57),
+            (9, 57),
+            (12, 57),
+            (16, 59),
+            (17, 59),
+            (25, 59),
+            (37, 60),
+            (65, 61),
+        ];
+        for (t, rounds_p) in cases {
+            assert_eq!(
+                round_numbers(t, Strength::Standard),
+                (8, rounds_p),
+                "t = {t}"
+            );
+        }
+        assert_eq!(round_numbers(3, Strength::Strengthened), (8, 69));
+        assert_eq!(round_numbers(12, Strength::Strengthened), (8, 72));
+    }
+
+    #[test]
+    fn test_domain_tags() {
+        let two = Fr::from(2u64);
+        assert_eq!(HashType::MerkleTree.domain_tag::<Fr>(2), Fr::from(3u64));
+        assert_eq!(HashType::MerkleTree.domain_tag::<Fr>(11), Fr::from(2047u64));
+        assert_eq!(
+            HashType::ConstantLength(4).domain_tag::<Fr>(2),
+            Fr::from(4u64) * two.pow([64])
+        );
+        assert_eq!(HashType::Encryption.domain_tag::<Fr>(8), two.pow([32]));
+        assert_eq!(
+            HashType::Custom(256).domain_tag::<Fr>(8),
+            Fr::from(256u64) * two.pow([40])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "custom domain tag id out of range")]
+    fn test_rejects_custom_zero() {
+        HashType::Custom(0).domain_tag::<Fr>(2);
+    }
+
+    #[test]
+    fn test_optimized_matches_reference() {
+        fn check<const T: usize>(instance: fn(Strength) -> PoseidonParameters<'static, Fr, T>) {
+            for strength in [Strength::Standard, Strength::Strengthened] {
+                let reference = ReferenceParameters::<Fr, T>::neptune(strength);
+                assert!(reference.agrees_with(&instance(strength)));
+            }
+        }
+        check(poseidon_3);
+        check(poseidon_5);
+        check(poseidon_9);
+        check(poseidon_12);
+    }
+
+    /// neptune's `hash_values` vectors: the Merkle hash of `[0, 1, ..., arity - 1]`, and
+    /// the `ConstantLength(4)` sponge digest of `[0, 1, 2, 3]`.
+    fn check_vectors<const T: usize>(
+        instance: fn(Strength) -> PoseidonParameters<'static, Fr, T>,
+        strength: Strength,
+        merkle: [u64; 4],
+        constant_length: [u64; 4],
+    ) {
+        let parameters = instance(strength);
+        let inputs = count::<T>();
+        assert_eq!(
+            hash(parameters, HashType::MerkleTree, &inputs[..T - 1]),
+            scalar(merkle)
+        );
+
+        let mut sponge = sponge(parameters, 4);
+        sponge.absorb(&count::<4>());
+        let digest = sponge.squeeze();
+        assert_eq!(digest, scalar(constant_length));
+        if T > 4 {
+            assert_eq!(
+                hash(parameters, HashType::ConstantLength(4), &count::<4>()),
+                digest
+            );
+        }
+    }
+
+    #[test]
+    fn test_standard_vectors() {
+        let strength = Strength::Standard;
+        check_vectors(
+            poseidon_3,
+            strength,
+            [
+                0x2e203c369a02e7ff,
+                0xa6fba9339d05a69d,
+                0x739e0fd902efe161,
+                0x396508d75e76a56b,
+            ],
+            [
+                0x1e12d20d3b71ec56,
+                0x7fb97ce0b8f66322,
+                0xc923003920c488d4,
+                0x19e8a3fe6c2df9ff,
+            ],
+        );
+        check_vectors(
+            poseidon_5,
+            strength,
+            [
+                0x019814ff6662075d,
+                0xfb6b4605bf1327ec,
+                0x00db3c6579229399,
+                0x58a54b10a9e5848a,
+            ],
+            [
+                0x8935b00a07909d45,
+                0x4984de08542c9977,
+                0x39443980077d7593,
+                0x3a21a6ae86754a29,
+            ],
+        );
+        check_vectors(
+            poseidon_9,
+            strength,
+            [
+                0x2a9934f56d38a5e6,
+                0x4b682e9d9cc4aed9,
+                0x1201004211677077,
+                0x2394611da3a5de55,
+            ],
+            [
+                0x370a94532f818897,
+                0x203e3c7c4a85c1f9,
+                0xcad8b9f8aeb1578f,
+                0x5c6de4b69de9d792,
+            ],
+        );
+        check_vectors(
+            poseidon_12,
+            strength,
+            [
+                0xcee3bbc32b693163,
+                0x09f3dcd8ccb08fc1,
+                0x6ca537e232ebe87a,
+                0x0c0fc1b2e5227f28,
+            ],
+            [
+                0xe9b0cb7d6496f73b,
+                0x7d2807d793af9582,
+                0xef841b6bf51a5a39,
+                0x02550c3a2113c7ca,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_strengthened_vectors() {
+        let strength = Strength::Strengthened;
+        check_vectors(
+            poseidon_3,
+            strength,
+            [
+                0x793dbaf54552cd69,
+                0x5278ecbf17040ea6,
+                0xc48b36ecc4cab748,
+                0x33d28a753baee41b,
+            ],
+            [
+                0xcbd4499072dcaff6,
+                0xdd21d8ebc5db51fb,
+                0x336c9c5c50e6a71e,
+                0x28156ad178f3a8fe,
+            ],
+        );
+        check_vectors(
+            poseidon_5,
+            strength,
+            [
+                0x4650ee190212aa9a,
+                0xe5113a254d6f5c7e,
+                0x54013bdaf68ba4c2,
+                0x09d8207c51ca3f43,
+            ],
+            [
+                0xa31d9dc66a42f972,
+                0xb5be830aae89db0d,
+                0xdff9a095d1d40420,
+                0x466e7819bb809c44,
+            ],
+        );
+        check_vectors(
+            poseidon_9,
+            strength,
+            [
+                0x9f0c3c93c3fc894e,
+                0xe843d4cfba662df1,
+                0xd69aae8fe1cb63e8,
+                0x69e61465981ae17e,
+            ],
+            [
+                0x6f2c393786312ee2,
+                0xadb6da339b87e590,
+                0xbf626c21fd6cb051,
+                0x0bb12009ab1fb62a,
+            ],
+        );
+        check_vectors(
+            poseidon_12,
+            strength,
+            [
+                0x778af344d8f9e8b7,
+                0xc94fe2ca3f46d433,
+                0x07abbcf9b406e8d8,
+                0x28bb83ff439753c0,
+            ],
+            [
+                0x6d14b130d0fc1ed5,
+                0x96e16aa48efc68a9,
+                0xf199e67d4e6e4bc7,
+                0x5ee31c86cd42e810,
+            ],
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Poseidon2 over the 31-bit fields on packed `u32` lanes.
+//!
+//! `Packed<P, N>` permutes `N` states at once: lane `i` of the state holds element `i` of
+//! every state as a `[u32; N]`, and every operation runs over the whole array, which the
+//! compiler turns into vector instructions. BabyBear and KoalaBear keep their lanes in
+//! Montgomery form with `R = 2^32`, so a product is one `u32 × u32` multiplication and a
+//! Montgomery reduction. Mersenne31 keeps canonical values and folds the high bits of a
+//! product onto the low ones. Sums of two values below `p < 2^31` fit a `u32`, and are
+//! corrected with a `min` rather than a branch.
+
+use core::marker::PhantomData;
+
+use crate::{
+    permutation::Arithmetic,
+    poseidon2::Poseidon2Parameters,
+    small_field::{element, value, SmallFp, SmallPrime},
+};
+
+/// A prime below `2^31` with a `u32` lane representation.
+pub trait Prime31: SmallPrime {
+    /// The lane holding the canonical value `value`.
+    fn to_lane(value: u32) -> u32;
+
+    /// The canonical value in `lane`.
+    fn from_lane(lane: u32) -> u32;
+
+    /// The lane of the product of the values in `a` and `b`.
+    fn mul_lanes(a: u32, b: u32) -> u32;
+}
+
+/// `x` below `2^32 · p` divided by `2^32` modulo `p`, where `mu` is `p^-1 mod 2^32`.
+#[inline]
+pub const fn monty_reduce(x: u64, p: u32, mu: u32) -> u32 {
+    let u = (x as u32).wrapping_mul(mu);
+    let (difference, borrow) = x.overflowing_sub(u as u64 * p as u64);
+    let high = (difference >> 32) as u32;
+    if borrow {
+        high.wrapping_add(p)
+    } else {
+        high
+    }
+}
+
+/// `value · 2^32 mod p`, the Montgomery form of `value`.
+#[inline]
+pub const fn monty_from_canonical(value: u32, p: u32) -> u32 {
+    (((value as u64) << 32) % p as u64) as u32
+}
+
+/// `2^31 = 1` modulo `2^31 - 1`, so the bits from 31 up fold onto the low ones. A
+/// product of canonical values folds below `2p`, and one subtraction is enough.
+#[inline]
+pub const fn mersenne31_mul(a: u32, b: u32) -> u32 {
+    const P: u32 = 0x7fff_ffff;
+    let product = a as u64 * b as u64;
+    let folded = (product as u32 & P) + (product >> 31) as u32;
+    if folded >= P {
+        folded - P
+    } else {
+        folded
+    }
+}
+
+/// Arithmetic on `N` states of the field modulo `P` at once.
+pub struct Packed<P, const N: usize>(PhantomData<P>);
+
+impl<P: Prime31, const N: usize> Packed<P, N> {
+    fn lane(c: &SmallFp<P>) -> u32 {
+        P::to_lane(value(c) as u32)
+    }
+
+    fn mul_lanes(a: &[u32; N], b: &[u32; N]) -> [u32; N] {
+        core::array::from_fn(|l| P::mul_lanes(a[l], b[l]))
+    }
+}
+
+impl<P: Prime31, const N: usize> Arithmetic<SmallFp<P>> for Packed<P, N> {
+    type Elem = [u32; N];
+
+    fn add(a: &[u32; N], b: &[u32; N]) -> [u32; N] {
+        let p = P::MODULUS as u32;
+        core::array::from_fn(|l| {
+            let sum = a[l] + b[l];
+            sum.min(sum.wrapping_sub(p))
+        })
+    }
+
+    fn add_constant(a: &[u32; N], c: &SmallFp<P>) -> [u32; N] {
+        Self::add(a, &[Self::lane(c); N])
+    }
+
+    fn mul(a: &[u32; N], c: &SmallFp<P>) -> [u32; N] {
+        let c = Self::lane(c);
+        a.map(|a| P::mul_lanes(a, c))
+    }
+
+    fn sbox(x: &[u32; N], alpha: u64) -> [u32; N] {
+        let x2 = Self::mul_lanes(x, x);
+        match alpha {
+            3 => Self::mul_lanes(&x2, x),
+            5 => Self::mul_lanes(&Self::mul_lanes(&x2, &x2), x),
+            7 => Self::mul_lanes(&Self::mul_lanes(&x2, &x2), &Self::mul_lanes(&x2, x)),
+            _ => {
+                let mut result = [P::to_lane(1); N];
+                for bit in (0..u64::BITS - alpha.leading_zeros()).rev() {
+                    result = Self::mul_lanes(&result, &result);
+                    if alpha >> bit & 1 == 1 {
+                        result = Self::mul_lanes(&result, x);
+                    }
+                }
+                result
+            }
+        }
+    }
+
+    fn mix<const T: usize>(state: &[[u32; N]; T], matrix: &[[SmallFp<P>; T]; T]) -> [[u32; N]; T] {
+        core::array::from_fn(|s| Self::mix_last(state, matrix, s))
+    }
+
+    fn mix_last<const T: usize>(
+        state: &[[u32; N]; T],
+        matrix: &[[SmallFp<P>; T]; T],
+        s: usize,
+    ) -> [u32; N] {
+        state
+            .iter()
+            .zip(matrix)
+            .map(|(val, row)| Self::mul(val, &row[s]))
+            .fold([0; N], |acc, term| Self::add(&acc, &term))
+    }
+}
+
+/// Permutes `N` states at once; state `l` ends as `parameters.permute` would leave it.
+pub fn permute_packed<P: Prime31, const T: usize, const N: usize>(
+    parameters: &Poseidon2Parameters<'_, SmallFp<P>, T>,
+    states: &mut [[SmallFp<P>; T]; N],
+) {
+    let mut lanes: [[u32; N]; T] =
+        core::array::from_fn(|i| core::array::from_fn(|l| P::to_lane(value(&states[l][i]) as u32)));
+    parameters.permute_with::<Packed<P, N>>(&mut lanes);
+    for (l, state) in states.iter_mut().enumerate() {
+        for (i, val) in state.iter_mut().enumerate() {
+            *val = element(P::from_lane(lanes[i][l]) as u64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{
+        babybear::{self, BabyBear, BabyBearPrime},
+        koalabear::{self, KoalaBearPrime},
+        mersenne31::{self, Mersenne31Prime},
+        permutation::Permutation,
+    };
+
+    fn check_lanes<P: Prime31>(a: u32, b: u32) -> Result<(), TestCaseError> {
+        let p = P::MODULUS as u32;
+        let (a, b) = (a % p, b % p);
+        prop_assert_eq!(P::from_lane(P::to_lane(a)), a);
+        let product = P::from_lane(P::mul_lanes(P::to_lane(a), P::to_lane(b)));
+        prop_assert_eq!(product as u64, a as u64 * b as u64 % P::MODULUS);
+        let sum = Packed::<P, 1>::add(&[P::to_lane(a)], &[P::to_lane(b)]);
+        prop_assert_eq!(
+            P::from_lane(sum[0]) as u64,
+            (a as u64 + b as u64) % P::MODULUS
+        );
+        Ok(())
+    }
+
+    fn check_permutation<P: Prime31, const T: usize>(
+        parameters: Poseidon2Parameters<'_, SmallFp<P>, T>,
+        seed: u64,
+    ) -> Result<(), TestCaseError> {
+        let mut states: [[SmallFp<P>; T]; 8] = core::array::from_fn(|l| {
+            core::array::from_fn(|i| SmallFp::<P>::from(seed.wrapping_mul(l as u64 + 1) ^ i as u64))
+        });
+        let expected = states.map(|mut state| {
+            parameters.permute(&mut state);
+            state
+        });
+        permute_packed(&parameters, &mut states);
+        prop_assert_eq!(states, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_known_answer() {
+        // Plonky3's `test_default_babybear_poseidon2_width_16`, in every lane.
+        let input: [u32; 16] = [
+            894848333, 1437655012, 1200606629, 1690012884, 71131202, 1749206695, 1717947831,
+            120589055, 19776022, 42382981, 1831865506, 724844064, 171220207, 1299207443, 227047920,
+            1783754913,
+        ];
+        let mut states = [input.map(BabyBear::from); 4];
+        permute_packed(&babybear::plonky3_16(), &mut states);
+        let expected: [u32; 16] = [
+            516096821, 90309867, 1101817252, 1660784290, 360715097, 1789519026, 1788910906,
+            563338433, 319524748, 1741414159, 1650859320, 894311162, 1121347488, 1692793758,
+            1052633829, 1344246938,
+        ];
+        assert_eq!(states, [expected.map(BabyBear::from); 4]);
+    }
+
+    proptest! {
+        #[test]
+        fn lanes_match_scalar(a in any::<u32>(), b in any::<u32>()) {
+            check_lanes::<BabyBearPrime>(a, b)?;
+            check_lanes::<KoalaBearPrime>(a, b)?;
+            check_lanes::<Mersenne31Prime>(a, b)?;
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn packed_matches_scalar(seed in any::<u64>()) {
+            check_permutation(babybear::poseidon2_16(), seed)?;
+            check_permutation(babybear::plonky3_24(), seed)?;
+            check_permutation(koalabear::poseidon2_16(), seed)?;
+            check_permutation(koalabear::poseidon2_24(), seed)?;
+            check_permutation(mersenne31::poseidon2_16(), seed)?;
+            check_permutation(mersenne31::poseidon2_24(), seed)?;
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Poseidon over the Pallas base field, as Zcash's Orchard uses it.
+//!
+//! Orchard's `P128Pow5T3` has width 3, `x^5`, 8 full rounds and 56 partial rounds. halo2
+//! draws its constants with the reference's Grain procedure (the seed's S-box field 0,
+//! and the first MDS matrix drawn), so they are generated on first use like the other
+//! instances. `hash` is halo2's constant-length sponge, which keeps its capacity in the
+//! last lane rather than the first.
+//!
+//! Mina's Kimchi instance over the same field is out of scope: its round constants, MDS
+//! matrix and Mina's test vectors were not available to check against.
+
+use ark_ff::fields::{Fp256, MontBackend, MontConfig};
+#[cfg(feature = "std")]
+use ark_ff::{AdditiveGroup, Field};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "std")]
+use crate::{
+    generate::{cached, OptimizedConstants, ReferenceParameters, SBOX_REFERENCE},
+    permutation::{Permutation, PoseidonParameters},
+};
+
+#[derive(MontConfig)]
+#[modulus = "28948022309329048855892746252171976963363056481941560715954676764349967630337"]
+#[generator = "5"]
+pub struct PallasConfig;
+/// The Pallas base field, which is also the Vesta scalar field.
+pub type Fp = Fp256<MontBackend<PallasConfig, 4>>;
+
+pub const ALPHA: u64 = 5;
+pub const ROUNDS_F: usize = 8;
+pub const ROUNDS_P: usize = 56;
+/// The rate of `hash`: lanes 0 and 1 absorb, lane 2 is the capacity.
+pub const RATE: usize = 2;
+
+/// Orchard's `P128Pow5T3` permutation.
+#[cfg(feature = "std")]
+pub fn p128_pow5_t3() -> PoseidonParameters<'static, Fp, 3> {
+    static CONSTANTS: OnceLock<OptimizedConstants<Fp, 3>> = OnceLock::new();
+    cached(&CONSTANTS, || {
+        ReferenceParameters::generate(SBOX_REFERENCE, ALPHA, ROUNDS_F, ROUNDS_P)
+    })
+}
+
+/// halo2's `ConstantLength` hash of `inputs`: the capacity starts at `len · 2^64`, the
+/// inputs are zero-padded to a multiple of `RATE` and added to lanes 0 and 1 with a
+/// permutation after each pair, and the digest is lane 0.
+#[cfg(feature = "std")]
+pub fn hash(inputs: &[Fp]) -> Fp {
+    let permutation = p128_pow5_t3();
+    let mut state = [Fp::ZERO; 3];
+    state[RATE] = Fp::from(inputs.len() as u64) * Fp::from(2u64).pow([64]);
+    for chunk in inputs.chunks(RATE) {
+        for (val, input) in state.iter_mut().zip(chunk) {
+            *val += input;
+        }
+        permutation.permute(&mut state);
+    }
+    if inputs.is_empty() {
+        permutation.permute(&mut state);
+    }
+    state[0]
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::generate::hex;
+
+    /// Zcash's `orchard_poseidon` permutation and hash vectors, from zcash-test-vectors
+    /// as halo2_poseidon ships them, written big-endian.
+    const ORCHARD_VECTORS: &str = include_str!("../vectors/orchard_poseidon.json");
+
+    fn elements(values: &Value) -> Vec<Fp> {
+        values
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| hex(value.as_str().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_reference_tables() {
+        // The first entries of halo2's `fp.rs` tables.
+        let reference =
+            ReferenceParameters::<Fp, 3>::generate(SBOX_REFERENCE, ALPHA, ROUNDS_F, ROUNDS_P);
+        assert_eq!(
+            reference.round_constants[0][0],
+            hex("0x360d7470611e473d353f628f76d110f34e71162f31003b7057538c2596426303")
+        );
+        assert_eq!(
+            reference.mds[0][0],
+            hex("0x0ab5e5b874a68de7b3d59fbdc8c9ead497d7a0ab23850b56323f2486d7e11b63")
+        );
+    }
+
+    #[test]
+    fn test_known_answer() {
+        let vectors: Value = serde_json::from_str(ORCHARD_VECTORS).unwrap();
+        let vectors = vectors["permute"].as_array().unwrap();
+        assert_eq!(vectors.len(), 11);
+        for vector in vectors {
+            let mut state: [Fp; 3] = elements(&vector["initial_state"]).try_into().unwrap();
+            p128_pow5_t3().permute(&mut state);
+            assert_eq!(state[..], elements(&vector["final_state"])[..]);
+        }
+    }
+
+    #[test]
+    fn test_optimized_matches_reference() {
+        let reference =
+            ReferenceParameters::<Fp, 3>::generate(SBOX_REFERENCE, ALPHA, ROUNDS_F, ROUNDS_P);
+        assert!(reference.agrees_with(&p128_pow5_t3()));
+    }
+
+    #[test]
+    fn test_hash() {
+        let tag = |len: u64| Fp::from(len) * Fp::from(2u64).pow([64]);
+        let (a, b, c) = (Fp::from(6), Fp::from(42), Fp::from(7));
+
+        // Orchard's two-element hash is one permutation of [a, b, 2 · 2^64].
+        let mut state = [a, b, tag(2)];
+        p128_pow5_t3().permute(&mut state);
+        assert_eq!(hash(&[a, b]), state[0]);
+
+        // An odd length is padded with a zero.
+        let mut state = [a, b, tag(3)];
+        p128_pow5_t3().permute(&mut state);
+        state[0] += c;
+        p128_pow5_t3().permute(&mut state);
+        assert_eq!(hash(&[a, b, c]), state[0]);
+    }
+
+    #[test]
+    fn test_hash_vectors() {
+        let vectors: Value = serde_json::from_str(ORCHARD_VECTORS).unwrap();
+        let vectors = vectors["hash"].as_array().unwrap();
+        assert_eq!(vectors.len(), 11);
+        for vector in vectors {
+            let input = elements(&vector["input"]);
+            assert_eq!(hash(&input), hex(vector["output"].as_str().unwrap()));
+        }
+    }
+}
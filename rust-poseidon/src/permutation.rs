@@ -0,0 +1,211 @@
+//! The permutation interface, and Poseidon parameters over any arkworks prime field.
+//!
+//! `PoseidonParameters` holds the constants in the optimized form circomlib uses: full
+//! rounds add `T` constants each, partial rounds add one constant to lane 0 and mix with
+//! a sparse matrix, and the middle round switches to the dense `p` matrix that makes
+//! the sparse ones possible. The engine in `functional.rs` runs any such instance;
+//! `CircomPoseidonConstants` is the BN254 one.
+
+use ark_ff::{Field, PrimeField};
+
+/// A fixed permutation of a width-`T` state.
+pub trait Permutation<const T: usize> {
+    type Field: Field;
+
+    fn permute(&self, state: &mut [Self::Field; T]);
+}
+
+impl<P: Permutation<T>, const T: usize> Permutation<T> for &P {
+    type Field = P::Field;
+
+    fn permute(&self, state: &mut [Self::Field; T]) {
+        (**self).permute(state)
+    }
+}
+
+/// The field operations the permutation is built from.
+///
+/// `Elem` is what one lane of the state holds: an element of `F`, or one element of each
+/// of several states permuted in lockstep, as `lanes::Lanes` does. `Ark` uses ark-ff's
+/// arithmetic and works for every prime field; a field can supply faster versions, as
+/// `mont::Bn254` does for the BN254 scalar field.
+pub trait Arithmetic<F> {
+    type Elem: Copy;
+
+    fn add(a: &Self::Elem, b: &Self::Elem) -> Self::Elem;
+
+    /// `a + c` for a round constant `c`.
+    fn add_constant(a: &Self::Elem, c: &F) -> Self::Elem;
+
+    /// `a * c` for a matrix entry `c`.
+    fn mul(a: &Self::Elem, c: &F) -> Self::Elem;
+
+    fn sbox(x: &Self::Elem, alpha: u64) -> Self::Elem;
+
+    /// `state · matrix`: output lane `j` is `sum_i state[i] * matrix[i][j]`.
+    fn mix<const T: usize>(state: &[Self::Elem; T], matrix: &[[F; T]; T]) -> [Self::Elem; T];
+
+    /// Lane `s` of `mix`.
+    fn mix_last<const T: usize>(
+        state: &[Self::Elem; T],
+        matrix: &[[F; T]; T],
+        s: usize,
+    ) -> Self::Elem;
+}
+
+/// ark-ff's arithmetic, for any prime field.
+pub struct Ark;
+
+impl<F: PrimeField> Arithmetic<F> for Ark {
+    type Elem = F;
+
+    fn add(a: &F, b: &F) -> F {
+        *a + b
+    }
+
+    fn add_constant(a: &F, c: &F) -> F {
+        *a + c
+    }
+
+    fn mul(a: &F, c: &F) -> F {
+        *a * c
+    }
+
+    fn sbox(x: &F, alpha: u64) -> F {
+        match alpha {
+            3 => x.square() * x,
+            5 => x.square().square() * x,
+            7 => x.square() * x.square().square() * x,
+            _ => x.pow([alpha]),
+        }
+    }
+
+    fn mix<const T: usize>(state: &[F; T], matrix: &[[F; T]; T]) -> [F; T] {
+        core::array::from_fn(|s| Self::mix_last(state, matrix, s))
+    }
+
+    fn mix_last<const T: usize>(state: &[F; T], matrix: &[[F; T]; T], s: usize) -> F {
+        state
+            .iter()
+            .zip(matrix)
+            .map(|(val, row)| *val * row[s])
+            .sum()
+    }
+}
+
+/// A Poseidon instance over `F` with a width-`T` state, in circomlib's optimized form.
+#[derive(Debug)]
+pub struct PoseidonParameters<'a, F, const T: usize> {
+    /// The S-box exponent.
+    pub alpha: u64,
+    pub rounds_f: usize,
+    pub rounds_p: usize,
+    /// `T` constants per full round plus one per partial round, with the second half's
+    /// first round folded into the partial rounds.
+    pub c: &'a [F],
+    /// The MDS matrix.
+    pub m: &'a [[F; T]; T],
+    /// The dense matrix of the last round before the partial rounds.
+    pub p: &'a [[F; T]; T],
+    /// `2T - 1` sparse matrix entries per partial round.
+    pub s: &'a [F],
+}
+
+impl<F, const T: usize> Clone for PoseidonParameters<'_, F, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F, const T: usize> Copy for PoseidonParameters<'_, F, T> {}
+
+impl<'a, F: PrimeField, const T: usize> PoseidonParameters<'a, F, T> {
+    /// Panics if the tables do not fit the round numbers, or if `rounds_f` is not a
+    /// positive even number.
+    pub fn new(
+        alpha: u64,
+        rounds_f: usize,
+        rounds_p: usize,
+        c: &'a [F],
+        m: &'a [[F; T]; T],
+        p: &'a [[F; T]; T],
+        s: &'a [F],
+    ) -> Self {
+        assert!(T >= 2, "the state needs at least two elements");
+        assert!(
+            rounds_f >= 2 && rounds_f.is_multiple_of(2),
+            "full rounds must be split evenly around the partial rounds"
+        );
+        assert_eq!(c.len(), T * rounds_f + rounds_p, "round constant count");
+        assert_eq!(s.len(), (2 * T - 1) * rounds_p, "sparse matrix entry count");
+        PoseidonParameters {
+            alpha,
+            rounds_f,
+            rounds_p,
+            c,
+            m,
+            p,
+            s,
+        }
+    }
+
+    /// Lane 0 of the permutation, the way circomlib hashes.
+    pub fn hash(&self, state: [F; T]) -> F {
+        crate::functional::hash_with::<F, T, Ark>(*self, state)
+    }
+}
+
+impl<F: PrimeField, const T: usize> Permutation<T> for PoseidonParameters<'_, F, T> {
+    type Field = F;
+
+    fn permute(&self, state: &mut [F; T]) {
+        *state = crate::functional::permute_with::<F, T, Ark>(*self, *state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[derive(MontConfig)]
+    #[modulus = "18446744069414584321"]
+    #[generator = "7"]
+    struct GoldilocksConfig;
+    type Goldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+    fn g(n: u64) -> Goldilocks {
+        Goldilocks::from(n)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn permutes_other_fields(x in any::<u64>(), y in any::<u64>()) {
+            // One full round, one partial round and one full round, written out.
+            let c = [g(1), g(2), g(3), g(4), g(5)];
+            let m = [[g(2), g(1)], [g(1), g(3)]];
+            let p = [[g(5), g(7)], [g(11), g(13)]];
+            let s = [g(17), g(19), g(23)];
+            let parameters = PoseidonParameters::new(3, 2, 1, &c, &m, &p, &s);
+
+            let mut state = [g(x), g(y)];
+            parameters.permute(&mut state);
+
+            let sbox = |v: Goldilocks| v * v * v;
+            let dense = |[a, b]: [Goldilocks; 2], m: &[[Goldilocks; 2]; 2]| {
+                [a * m[0][0] + b * m[1][0], a * m[0][1] + b * m[1][1]]
+            };
+            let mut expected = [g(x) + c[0], g(y) + c[1]];
+            expected = dense([sbox(expected[0]) + c[2], sbox(expected[1]) + c[3]], &p);
+            let x0 = sbox(expected[0]) + c[4];
+            expected = [x0 * s[0] + expected[1] * s[1], expected[1] + x0 * s[2]];
+            expected = dense(expected.map(sbox), &m);
+            prop_assert_eq!(state, expected);
+            prop_assert_eq!(parameters.hash([g(x), g(y)]), expected[0]);
+        }
+    }
+}
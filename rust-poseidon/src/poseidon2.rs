@@ -0,0 +1,271 @@
+//! Poseidon2 over any arkworks prime field.
+//!
+//! Poseidon2 keeps Poseidon's rounds but replaces its dense MDS matrix with two cheap
+//! ones. Full rounds mix with the "external" matrix, built from additions only: `circ(2,
+//! 1)` and `circ(2, 1, 1)` for widths 2 and 3, and for multiples of 4 a 4x4 matrix on
+//! every block of four followed by adding the sum of the blocks to each. The reference
+//! implementation and Plonky3 pick different 4x4 matrices; see `M4`. Partial rounds
+//! mix with the "internal" matrix, the all-ones matrix plus a diagonal, which costs one
+//! multiplication per lane. The external matrix is also applied once before the first
+//! round.
+
+use ark_ff::PrimeField;
+
+use crate::permutation::{Arithmetic, Ark, Permutation};
+
+/// The 4x4 matrix the external matrix applies to each block of four lanes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum M4 {
+    /// `[[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]]`, the reference
+    /// implementation's, which Noir and RISC Zero also use.
+    Horizen,
+    /// `[[2, 3, 1, 1], [1, 2, 3, 1], [1, 1, 2, 3], [3, 1, 1, 2]]`, Plonky3's `MDSMat4`.
+    Plonky3,
+}
+
+/// A Poseidon2 instance over `F` with a width-`T` state.
+#[derive(Debug)]
+pub struct Poseidon2Parameters<'a, F, const T: usize> {
+    /// The S-box exponent.
+    pub alpha: u64,
+    pub rounds_f: usize,
+    pub rounds_p: usize,
+    /// `T` constants for each full round.
+    pub external: &'a [[F; T]],
+    /// One constant for each partial round, added to lane 0.
+    pub internal: &'a [F],
+    /// The internal matrix minus the all-ones matrix.
+    pub diagonal: &'a [F; T],
+    /// The blocks of the external matrix, for widths from 4 on.
+    pub m4: M4,
+}
+
+impl<F, const T: usize> Clone for Poseidon2Parameters<'_, F, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F, const T: usize> Copy for Poseidon2Parameters<'_, F, T> {}
+
+impl<'a, F: PrimeField, const T: usize> Poseidon2Parameters<'a, F, T> {
+    /// An instance with the reference's `M4::Horizen` blocks.
+    ///
+    /// Panics if there is no external matrix for `T`, if the tables do not fit the round
+    /// numbers, or if `rounds_f` is not a positive even number.
+    pub fn new(
+        alpha: u64,
+        rounds_f: usize,
+        rounds_p: usize,
+        external: &'a [[F; T]],
+        internal: &'a [F],
+        diagonal: &'a [F; T],
+    ) -> Self {
+        assert!(
+            T == 2 || T == 3 || (T >= 4 && T.is_multiple_of(4)),
+            "Poseidon2 is defined for widths 2, 3 and multiples of 4"
+        );
+        assert!(
+            rounds_f >= 2 && rounds_f.is_multiple_of(2),
+            "full rounds must be split evenly around the partial rounds"
+        );
+        assert_eq!(external.len(), rounds_f, "full round constant count");
+        assert_eq!(internal.len(), rounds_p, "partial round constant count");
+        Poseidon2Parameters {
+            alpha,
+            rounds_f,
+            rounds_p,
+            external,
+            internal,
+            diagonal,
+            m4: M4::Horizen,
+        }
+    }
+
+    /// The same instance with `m4` as the blocks of its external matrix.
+    pub fn with_m4(self, m4: M4) -> Self {
+        Poseidon2Parameters { m4, ..self }
+    }
+
+    /// The permutation, computed with the arithmetic `A`.
+    pub(crate) fn permute_with<A: Arithmetic<F>>(&self, state: &mut [A::Elem; T]) {
+        let (first, second) = self.external.split_at(self.rounds_f / 2);
+        mix_external::<F, A, T>(state, self.m4);
+        for constants in first {
+            self.full_round::<A>(state, constants);
+        }
+        for constant in self.internal {
+            state[0] = A::sbox(&A::add_constant(&state[0], constant), self.alpha);
+            self.mix_internal::<A>(state);
+        }
+        for constants in second {
+            self.full_round::<A>(state, constants);
+        }
+    }
+
+    fn full_round<A: Arithmetic<F>>(&self, state: &mut [A::Elem; T], constants: &[F; T]) {
+        for (val, c) in state.iter_mut().zip(constants) {
+            *val = A::sbox(&A::add_constant(val, c), self.alpha);
+        }
+        mix_external::<F, A, T>(state, self.m4);
+    }
+
+    fn mix_internal<A: Arithmetic<F>>(&self, state: &mut [A::Elem; T]) {
+        let sum = sum::<F, A>(state);
+        for (val, d) in state.iter_mut().zip(self.diagonal) {
+            *val = A::add(&A::mul(val, d), &sum);
+        }
+    }
+}
+
+impl<F: PrimeField, const T: usize> Permutation<T> for Poseidon2Parameters<'_, F, T> {
+    type Field = F;
+
+    fn permute(&self, state: &mut [F; T]) {
+        self.permute_with::<Ark>(state);
+    }
+}
+
+fn sum<F, A: Arithmetic<F>>(values: &[A::Elem]) -> A::Elem {
+    values[1..]
+        .iter()
+        .fold(values[0], |acc, val| A::add(&acc, val))
+}
+
+/// The external matrix, with `m4` as its blocks.
+fn mix_external<F, A: Arithmetic<F>, const T: usize>(state: &mut [A::Elem; T], m4: M4) {
+    match T {
+        2 | 3 => {
+            let sum = sum::<F, A>(state);
+            for val in state.iter_mut() {
+                *val = A::add(val, &sum);
+            }
+        }
+        4 => mix_m4::<F, A>(state.as_mut_slice().try_into().unwrap(), m4),
+        _ => {
+            for block in state.chunks_exact_mut(4) {
+                mix_m4::<F, A>(block.try_into().unwrap(), m4);
+            }
+            let mut sums: [A::Elem; 4] = state[..4].try_into().unwrap();
+            for block in state[4..].chunks_exact(4) {
+                for (sum, val) in sums.iter_mut().zip(block) {
+                    *sum = A::add(sum, val);
+                }
+            }
+            for (i, val) in state.iter_mut().enumerate() {
+                *val = A::add(val, &sums[i % 4]);
+            }
+        }
+    }
+}
+
+/// One block of four: `M4::Horizen` in the reference's eight additions and four
+/// doublings, `M4::Plonky3` in Plonky3's seven additions and two doublings.
+fn mix_m4<F, A: Arithmetic<F>>(x: &mut [A::Elem; 4], m4: M4) {
+    let double = |x: &A::Elem| A::add(x, x);
+    let t0 = A::add(&x[0], &x[1]);
+    let t1 = A::add(&x[2], &x[3]);
+    match m4 {
+        M4::Horizen => {
+            let t2 = A::add(&double(&x[1]), &t1);
+            let t3 = A::add(&double(&x[3]), &t0);
+            let t4 = A::add(&double(&double(&t1)), &t3);
+            let t5 = A::add(&double(&double(&t0)), &t2);
+            *x = [A::add(&t3, &t5), t5, A::add(&t2, &t4), t4];
+        }
+        M4::Plonky3 => {
+            let sum = A::add(&t0, &t1);
+            let t2 = A::add(&sum, &x[1]);
+            let t3 = A::add(&sum, &x[3]);
+            *x = [
+                A::add(&t2, &t0),
+                A::add(&t2, &double(&x[2])),
+                A::add(&t3, &t1),
+                A::add(&t3, &double(&x[0])),
+            ];
+        }
+    }
+}
+
+/// Panics unless `parameters` has the round constants the reference's Grain procedure
+/// draws for it, in its order: the first half's full rounds, the partial rounds, then the
+/// second half's.
+#[cfg(test)]
+pub(crate) fn assert_grain_constants<F: PrimeField, const T: usize>(
+    parameters: &Poseidon2Parameters<F, T>,
+) {
+    use crate::generate::{Grain, SBOX_REFERENCE};
+
+    let (rounds_f, rounds_p) = (parameters.rounds_f, parameters.rounds_p);
+    let mut grain = Grain::new(SBOX_REFERENCE, F::MODULUS_BIT_SIZE, T, rounds_f, rounds_p);
+    let (first, second) = parameters.external.split_at(rounds_f / 2);
+    let drawn = first
+        .iter()
+        .flatten()
+        .chain(parameters.internal)
+        .chain(second.iter().flatten());
+    for constant in drawn {
+        assert_eq!(*constant, grain.next_field_element::<F>());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::AdditiveGroup;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::field::{arb_fr, Fr};
+
+    fn dense<const T: usize>(state: [Fr; T], matrix: [[u64; T]; T]) -> [Fr; T] {
+        core::array::from_fn(|i| {
+            (0..T)
+                .map(|j| Fr::from(matrix[i][j]) * state[j])
+                .sum::<Fr>()
+        })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn external_matrices(state in proptest::array::uniform8(arb_fr())) {
+            let mut two = [state[0], state[1]];
+            mix_external::<Fr, Ark, 2>(&mut two, M4::Horizen);
+            prop_assert_eq!(two, dense([state[0], state[1]], [[2, 1], [1, 2]]));
+
+            let mut three = [state[0], state[1], state[2]];
+            mix_external::<Fr, Ark, 3>(&mut three, M4::Horizen);
+            let circulant = [[2, 1, 1], [1, 2, 1], [1, 1, 2]];
+            prop_assert_eq!(three, dense([state[0], state[1], state[2]], circulant));
+
+            let horizen = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+            let plonky3 = [[2, 3, 1, 1], [1, 2, 3, 1], [1, 1, 2, 3], [3, 1, 1, 2]];
+            for (m4, matrix) in [(M4::Horizen, horizen), (M4::Plonky3, plonky3)] {
+                let mut four = [state[0], state[1], state[2], state[3]];
+                mix_external::<Fr, Ark, 4>(&mut four, m4);
+                prop_assert_eq!(four, dense([state[0], state[1], state[2], state[3]], matrix));
+
+                // circ(2 M4, M4) for two blocks.
+                let mut eight = state;
+                mix_external::<Fr, Ark, 8>(&mut eight, m4);
+                let expected = core::array::from_fn(|i| {
+                    (0..8)
+                        .map(|j| {
+                            let entry = matrix[i % 4][j % 4] * if i / 4 == j / 4 { 2 } else { 1 };
+                            Fr::from(entry) * state[j]
+                        })
+                        .sum::<Fr>()
+                });
+                prop_assert_eq!(eight, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "widths 2, 3 and multiples of 4")]
+    fn test_rejects_width_5() {
+        let diagonal = [Fr::ZERO; 5];
+        Poseidon2Parameters::new(5, 2, 0, &[[Fr::ZERO; 5]; 2], &[], &diagonal);
+    }
+}
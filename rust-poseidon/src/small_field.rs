@@ -0,0 +1,266 @@
+//! An ark-ff backend for prime fields below `2^64`, shared by the small-field instances.
+//!
+//! Elements are kept as canonical `u64`s in a single limb. Sums and differences wrap and
+//! are corrected once, so they are right for any modulus up to `2^64`; the reduction of
+//! a product is the one thing each prime supplies (`SmallPrime::mul`), since it is where
+//! the shape of the modulus pays off. `SmallField<P>` is the `FpConfig` for a prime `P`,
+//! and a field is `Fp64<SmallField<P>>`, an arkworks `PrimeField` like any other.
+
+use core::marker::PhantomData;
+
+use ark_ff::{
+    fields::{Fp, Fp64, FpConfig},
+    BigInt, Field, SqrtPrecomputation,
+};
+
+/// A prime below `2^64`, with what ark-ff needs to know about it.
+pub trait SmallPrime: Send + Sync + 'static + Sized {
+    const MODULUS: u64;
+    /// A multiplicative generator, which is also a quadratic non-residue.
+    const GENERATOR: u64;
+    /// The number of factors of two in `MODULUS - 1`.
+    const TWO_ADICITY: u32;
+    /// `GENERATOR^((MODULUS - 1) / 2^TWO_ADICITY)`.
+    const TWO_ADIC_ROOT_OF_UNITY: u64;
+
+    /// `a · b mod MODULUS`, for canonical `a` and `b`.
+    fn mul(a: u64, b: u64) -> u64;
+}
+
+/// The `FpConfig` of the prime `P`.
+pub struct SmallField<P>(PhantomData<P>);
+
+pub type SmallFp<P> = Fp64<SmallField<P>>;
+
+/// The element whose canonical value is `value`, which must be below the modulus.
+pub const fn element<P: SmallPrime>(value: u64) -> SmallFp<P> {
+    Fp(BigInt([value]), PhantomData)
+}
+
+/// The canonical value of `element`.
+pub fn value<P: SmallPrime>(element: &SmallFp<P>) -> u64 {
+    element.0 .0[0]
+}
+
+/// A table of canonical 32-bit values as elements, for tables converted at compile time.
+pub const fn elements<P: SmallPrime, const N: usize>(values: &[u32; N]) -> [SmallFp<P>; N] {
+    let mut out = [element(0); N];
+    let mut i = 0;
+    while i < N {
+        assert!(
+            (values[i] as u64) < P::MODULUS,
+            "table entry is not reduced"
+        );
+        out[i] = element(values[i] as u64);
+        i += 1;
+    }
+    out
+}
+
+/// `elements` for each row of a table.
+pub const fn rows<P: SmallPrime, const T: usize, const R: usize>(
+    values: &[[u32; T]; R],
+) -> [[SmallFp<P>; T]; R] {
+    let mut out = [[element(0); T]; R];
+    let mut i = 0;
+    while i < R {
+        out[i] = elements(&values[i]);
+        i += 1;
+    }
+    out
+}
+
+/// `numerator / 2^shift`, the form Plonky3 gives its internal diagonals in.
+#[cfg(test)]
+pub(crate) fn dyadic<P: SmallPrime>(numerator: i64, shift: u32) -> SmallFp<P> {
+    let magnitude = SmallFp::<P>::from(numerator.unsigned_abs())
+        * SmallFp::<P>::from(2u64)
+            .pow([shift as u64])
+            .inverse()
+            .unwrap();
+    if numerator < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+impl<P: SmallPrime> FpConfig<1> for SmallField<P> {
+    const MODULUS: BigInt<1> = BigInt([P::MODULUS]);
+    const GENERATOR: SmallFp<P> = element(P::GENERATOR);
+    const ZERO: SmallFp<P> = element(0);
+    const ONE: SmallFp<P> = element(1);
+    const TWO_ADICITY: u32 = P::TWO_ADICITY;
+    const TWO_ADIC_ROOT_OF_UNITY: SmallFp<P> = element(P::TWO_ADIC_ROOT_OF_UNITY);
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<SmallFp<P>>> =
+        Some(SqrtPrecomputation::TonelliShanks {
+            two_adicity: P::TWO_ADICITY,
+            quadratic_nonresidue_to_trace: element(P::TWO_ADIC_ROOT_OF_UNITY),
+            // `(t - 1) / 2` for the odd part `t` of `p - 1`.
+            trace_of_modulus_minus_one_div_two: &[(P::MODULUS >> P::TWO_ADICITY) / 2],
+        });
+
+    fn add_assign(a: &mut SmallFp<P>, b: &SmallFp<P>) {
+        let (sum, carry) = value(a).overflowing_add(value(b));
+        *a = element(if carry || sum >= P::MODULUS {
+            sum.wrapping_sub(P::MODULUS)
+        } else {
+            sum
+        });
+    }
+
+    fn sub_assign(a: &mut SmallFp<P>, b: &SmallFp<P>) {
+        let (difference, borrow) = value(a).overflowing_sub(value(b));
+        *a = element(if borrow {
+            difference.wrapping_add(P::MODULUS)
+        } else {
+            difference
+        });
+    }
+
+    fn double_in_place(a: &mut SmallFp<P>) {
+        let b = *a;
+        Self::add_assign(a, &b);
+    }
+
+    fn neg_in_place(a: &mut SmallFp<P>) {
+        if value(a) != 0 {
+            *a = element(P::MODULUS - value(a));
+        }
+    }
+
+    fn mul_assign(a: &mut SmallFp<P>, b: &SmallFp<P>) {
+        *a = element(P::mul(value(a), value(b)));
+    }
+
+    fn sum_of_products<const T: usize>(a: &[SmallFp<P>; T], b: &[SmallFp<P>; T]) -> SmallFp<P> {
+        a.iter().zip(b).map(|(a, b)| *a * b).sum()
+    }
+
+    fn square_in_place(a: &mut SmallFp<P>) {
+        let b = *a;
+        Self::mul_assign(a, &b);
+    }
+
+    fn inverse(a: &SmallFp<P>) -> Option<SmallFp<P>> {
+        (value(a) != 0).then(|| a.pow([P::MODULUS - 2]))
+    }
+
+    fn from_bigint(other: BigInt<1>) -> Option<SmallFp<P>> {
+        (other.0[0] < P::MODULUS).then(|| element(other.0[0]))
+    }
+
+    fn into_bigint(other: SmallFp<P>) -> BigInt<1> {
+        other.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{AdditiveGroup, PrimeField};
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{
+        babybear::BabyBearPrime, goldilocks::GoldilocksPrime, koalabear::KoalaBearPrime,
+        mersenne31::Mersenne31Prime,
+    };
+
+    fn arb<P: SmallPrime>() -> impl Strategy<Value = SmallFp<P>> {
+        prop_oneof![
+            (0..P::MODULUS).prop_map(element::<P>),
+            (0..1024u64).prop_map(|n| -SmallFp::<P>::from(n)),
+        ]
+    }
+
+    fn reduce<P: SmallPrime>(x: u128) -> u64 {
+        (x % P::MODULUS as u128) as u64
+    }
+
+    /// Every operation of the backend against `u128` arithmetic.
+    fn check_arithmetic<P: SmallPrime>(a: SmallFp<P>, b: SmallFp<P>) -> Result<(), TestCaseError> {
+        let (x, y, p) = (value(&a) as u128, value(&b) as u128, P::MODULUS as u128);
+        prop_assert_eq!(value(&(a + b)), reduce::<P>(x + y));
+        prop_assert_eq!(value(&(a - b)), reduce::<P>(x + p - y));
+        prop_assert_eq!(value(&a.double()), reduce::<P>(2 * x));
+        prop_assert_eq!(value(&(a * b)), reduce::<P>(x * y));
+        prop_assert_eq!(value(&a.square()), reduce::<P>(x * x));
+        prop_assert_eq!(value(&-a), reduce::<P>(p - x));
+        if let Some(inverse) = a.inverse() {
+            prop_assert_eq!(inverse * a, SmallFp::<P>::ONE);
+        }
+        prop_assert_eq!(a.square().sqrt().map(|r| r.square()), Some(a.square()));
+        Ok(())
+    }
+
+    /// The constants of `P` against their definitions.
+    fn check_constants<P: SmallPrime>() {
+        let adicity = P::TWO_ADICITY;
+        assert_eq!((P::MODULUS - 1) >> adicity & 1, 1, "odd part");
+        let generator = element::<P>(P::GENERATOR);
+        assert_eq!(
+            generator.pow([(P::MODULUS - 1) / 2]),
+            -SmallFp::<P>::ONE,
+            "non-residue"
+        );
+        let root = element::<P>(P::TWO_ADIC_ROOT_OF_UNITY);
+        assert_eq!(root, generator.pow([(P::MODULUS - 1) >> adicity]));
+        assert_eq!(root.pow([1 << adicity]), SmallFp::<P>::ONE);
+        assert_ne!(root.pow([1 << (adicity - 1)]), SmallFp::<P>::ONE);
+        assert_eq!(
+            SmallFp::<P>::MODULUS_BIT_SIZE,
+            64 - P::MODULUS.leading_zeros()
+        );
+        assert_eq!(SmallFp::<P>::from(P::MODULUS), SmallFp::<P>::ZERO);
+        let minus_one = element::<P>(P::MODULUS - 1);
+        assert_eq!(minus_one * minus_one, SmallFp::<P>::ONE);
+    }
+
+    #[test]
+    fn test_constants() {
+        check_constants::<GoldilocksPrime>();
+        check_constants::<BabyBearPrime>();
+        check_constants::<KoalaBearPrime>();
+        check_constants::<Mersenne31Prime>();
+    }
+
+    #[test]
+    fn test_elements() {
+        let table = elements::<BabyBearPrime, 2>(&[0, 0x7800_0000]);
+        assert_eq!(table, [SmallFp::ZERO, -SmallFp::ONE]);
+        assert_eq!(
+            rows::<BabyBearPrime, 1, 2>(&[[1], [2]]),
+            [[SmallFp::from(1u32)], [SmallFp::from(2u32)]]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "table entry is not reduced")]
+    fn test_elements_rejects_the_modulus() {
+        elements::<Mersenne31Prime, 1>(&[0x7fff_ffff]);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        #[test]
+        fn goldilocks_arithmetic(a in arb::<GoldilocksPrime>(), b in arb::<GoldilocksPrime>()) {
+            check_arithmetic(a, b)?;
+        }
+
+        #[test]
+        fn baby_bear_arithmetic(a in arb::<BabyBearPrime>(), b in arb::<BabyBearPrime>()) {
+            check_arithmetic(a, b)?;
+        }
+
+        #[test]
+        fn koala_bear_arithmetic(a in arb::<KoalaBearPrime>(), b in arb::<KoalaBearPrime>()) {
+            check_arithmetic(a, b)?;
+        }
+
+        #[test]
+        fn mersenne31_arithmetic(a in arb::<Mersenne31Prime>(), b in arb::<Mersenne31Prime>()) {
+            check_arithmetic(a, b)?;
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! A sponge over any `Permutation`, for hashing inputs of any length.
+//!
+//! The state is laid out as in circomlib: the `T - rate` capacity elements come first
+//! and the rate elements after them. Inputs are added to the rate elements and outputs
+//! read from them, with a permutation whenever they are used up. There is no padding:
+//! messages of different lengths must be told apart through the initial capacity value,
+//! e.g. by setting it to the input length.
+
+use ark_ff::AdditiveGroup;
+
+use crate::permutation::Permutation;
+
+pub struct Sponge<P: Permutation<T>, const T: usize> {
+    permutation: P,
+    state: [P::Field; T],
+    rate: usize,
+    position: usize,
+    squeezing: bool,
+}
+
+impl<P: Permutation<T>, const T: usize> Sponge<P, T> {
+    /// Starts with every capacity element set to `capacity` and a zero rate part.
+    ///
+    /// Panics unless `0 < rate < T`.
+    pub fn new(permutation: P, rate: usize, capacity: P::Field) -> Self {
+        assert!(
+            rate > 0 && rate < T,
+            "rate must leave room for the capacity"
+        );
+        let mut state = [P::Field::ZERO; T];
+        state[..T - rate].fill(capacity);
+        Sponge {
+            permutation,
+            state,
+            rate,
+            position: 0,
+            squeezing: false,
+        }
+    }
+
+    pub fn absorb(&mut self, inputs: &[P::Field]) {
+        if self.squeezing {
+            self.permutation.permute(&mut self.state);
+            self.position = 0;
+            self.squeezing = false;
+        }
+        for input in inputs {
+            if self.position == self.rate {
+                self.permutation.permute(&mut self.state);
+                self.position = 0;
+            }
+            self.state[T - self.rate + self.position] += input;
+            self.position += 1;
+        }
+    }
+
+    pub fn squeeze(&mut self) -> P::Field {
+        if !self.squeezing || self.position == self.rate {
+            self.permutation.permute(&mut self.state);
+            self.position = 0;
+            self.squeezing = true;
+        }
+        let output = self.state[T - self.rate + self.position];
+        self.position += 1;
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{
+        constants::CircomPoseidonConstants,
+        field::{arb_fr, Fr},
+        functional::poseidon_permutation,
+    };
+
+    fn sponge() -> Sponge<&'static CircomPoseidonConstants, 3> {
+        Sponge::new(CircomPoseidonConstants::shared(), 2, Fr::from(0))
+    }
+
+    #[test]
+    fn test_sponge_single_block() {
+        let mut sponge = sponge();
+        sponge.absorb(&[Fr::from(1), Fr::from(2)]);
+
+        let state = poseidon_permutation([Fr::from(0), Fr::from(1), Fr::from(2)]);
+        assert_eq!(sponge.squeeze(), state[1]);
+        assert_eq!(sponge.squeeze(), state[2]);
+        assert_eq!(sponge.squeeze(), poseidon_permutation(state)[1]);
+    }
+
+    #[test]
+    fn test_sponge_two_blocks() {
+        let mut sponge = Sponge::new(CircomPoseidonConstants::shared(), 2, Fr::from(3));
+        sponge.absorb(&[Fr::from(1), Fr::from(2), Fr::from(3)]);
+
+        let mut state = poseidon_permutation([Fr::from(3), Fr::from(1), Fr::from(2)]);
+        state[1] += Fr::from(3);
+        assert_eq!(sponge.squeeze(), poseidon_permutation(state)[1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "rate must leave room")]
+    fn test_sponge_rejects_full_rate() {
+        Sponge::new(CircomPoseidonConstants::shared(), 3, Fr::from(0));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn absorb_is_incremental(inputs in proptest::collection::vec(arb_fr(), 0..8), split in 0..8usize) {
+            let mut whole = sponge();
+            whole.absorb(&inputs);
+
+            let mut pieces = sponge();
+            let (first, second) = inputs.split_at(split.min(inputs.len()));
+            pieces.absorb(first);
+            pieces.absorb(second);
+
+            prop_assert_eq!(whole.squeeze(), pieces.squeeze());
+            prop_assert_eq!(whole.squeeze(), pieces.squeeze());
+        }
+
+        #[test]
+        fn generic_arithmetic_matches_bn254(inputs in proptest::collection::vec(arb_fr(), 0..6)) {
+            let constants = CircomPoseidonConstants::shared();
+            let mut bn254 = Sponge::new(constants, 2, Fr::from(7));
+            let mut generic = Sponge::new(constants.parameters(), 2, Fr::from(7));
+            bn254.absorb(&inputs);
+            generic.absorb(&inputs);
+            prop_assert_eq!(bn254.squeeze(), generic.squeeze());
+        }
+    }
+}
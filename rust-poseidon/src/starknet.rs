@@ -0,0 +1,235 @@
+//! Starknet's Poseidon over the Stark field.
+//!
+//! Starknet uses a width-3 Hades permutation with `x^3`, 8 full rounds and 83 partial
+//! rounds. Unlike circomlib's, its partial rounds apply the S-box to the last lane, and it
+//! mixes with the cheap matrix `MDS` rather than an MDS matrix drawn from the Grain stream.
+//! Round key `i` is StarkWare's, the SHA-256 digest of `Hades{i}` reduced modulo p, as
+//! listed in `poseidon3.txt`. The permutation runs on the shared engine with its lanes
+//! reversed, and the hashes below are the ones Cairo's `poseidon_hash`,
+//! `poseidon_hash_single` and `poseidon_hash_many` (`poseidon_hash_span`) compute.
+
+use ark_ff::fields::{Fp256, MontBackend, MontConfig};
+#[cfg(feature = "std")]
+use ark_ff::{AdditiveGroup, Field, PrimeField};
+#[cfg(feature = "std")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "std")]
+use crate::{
+    generate::{cached, OptimizedConstants, ReferenceParameters},
+    permutation::{Permutation, PoseidonParameters},
+};
+
+#[derive(MontConfig)]
+#[modulus = "3618502788666131213697322783095070105623107215331596699973092056135872020481"]
+#[generator = "3"]
+pub struct FeltConfig;
+/// An element of the Stark field, modulo `2^251 + 17 · 2^192 + 1`.
+pub type Felt = Fp256<MontBackend<FeltConfig, 4>>;
+
+pub const ALPHA: u64 = 3;
+pub const ROUNDS_F: usize = 8;
+pub const ROUNDS_P: usize = 83;
+/// Starknet's mixing matrix.
+pub const MDS: [[i64; 3]; 3] = [[3, 1, 1], [1, -1, 1], [1, 1, -2]];
+
+/// Round key `i`: the SHA-256 digest of `Hades{i}`, reduced modulo p.
+#[cfg(feature = "std")]
+pub fn round_key(i: usize) -> Felt {
+    Felt::from_be_bytes_mod_order(&Sha256::digest(format!("Hades{i}")))
+}
+
+#[cfg(feature = "std")]
+impl ReferenceParameters<Felt, 3> {
+    /// Starknet's instance with its lanes reversed, so that the partial rounds' S-box is on
+    /// lane 0, where the engine applies it.
+    pub fn starknet() -> Self {
+        let round_constants = (0..ROUNDS_F + ROUNDS_P)
+            .map(|r| core::array::from_fn(|i| round_key(3 * r + 2 - i)))
+            .collect();
+        let mds = core::array::from_fn(|i| core::array::from_fn(|j| Felt::from(MDS[2 - i][2 - j])));
+        ReferenceParameters {
+            alpha: ALPHA,
+            rounds_f: ROUNDS_F,
+            rounds_p: ROUNDS_P,
+            round_constants,
+            mds,
+        }
+    }
+}
+
+/// Starknet's permutation on reversed lanes, in the engine's optimized form. The round
+/// keys are derived on first use.
+#[cfg(feature = "std")]
+pub fn hades() -> PoseidonParameters<'static, Felt, 3> {
+    static CONSTANTS: OnceLock<OptimizedConstants<Felt, 3>> = OnceLock::new();
+    cached(&CONSTANTS, ReferenceParameters::starknet)
+}
+
+/// Starknet's permutation, as Cairo's `hades_permutation` exposes it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Hades;
+
+#[cfg(feature = "std")]
+impl Permutation<3> for Hades {
+    type Field = Felt;
+
+    fn permute(&self, state: &mut [Felt; 3]) {
+        state.reverse();
+        hades().permute(state);
+        state.reverse();
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn hades_permutation(state: &mut [Felt; 3]) {
+    Hades.permute(state);
+}
+
+/// The hash of two elements: the permutation of `[x, y, 2]`, lane 0.
+#[cfg(feature = "std")]
+pub fn poseidon_hash(x: Felt, y: Felt) -> Felt {
+    let mut state = [x, y, Felt::from(2u64)];
+    hades_permutation(&mut state);
+    state[0]
+}
+
+/// The hash of one element: the permutation of `[x, 0, 1]`, lane 0.
+#[cfg(feature = "std")]
+pub fn poseidon_hash_single(x: Felt) -> Felt {
+    let mut state = [x, Felt::ZERO, Felt::ONE];
+    hades_permutation(&mut state);
+    state[0]
+}
+
+/// The hash of any number of elements: a rate-2 sponge whose message is padded with a
+/// single 1, so that every length is told apart.
+#[cfg(feature = "std")]
+pub fn poseidon_hash_many(inputs: &[Felt]) -> Felt {
+    let mut state = [Felt::ZERO; 3];
+    let (pairs, rest) = inputs.as_chunks::<2>();
+    for [x, y] in pairs {
+        state[0] += x;
+        state[1] += y;
+        hades_permutation(&mut state);
+    }
+    if let [x] = rest {
+        state[0] += x;
+    }
+    state[rest.len()] += Felt::ONE;
+    hades_permutation(&mut state);
+    state[0]
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::str::FromStr;
+
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::generate::hex;
+
+    // The vectors below were generated with cairo-lang v0.11.0 and are the ones
+    // starknet-crypto tests against.
+
+    #[test]
+    fn test_poseidon_hash() {
+        let cases = [
+            (
+                "0xb662f9017fa7956fd70e26129b1833e10ad000fd37b4d9f4e0ce6884b7bbe",
+                "0x1fe356bf76102cdae1bfbdc173602ead228b12904c00dad9cf16e035468bea",
+                "0x75540825a6ecc5dc7d7c2f5f868164182742227f1367d66c43ee51ec7937a81",
+            ),
+            (
+                "0xf4e01b2032298f86b539e3d3ac05ced20d2ef275273f9325f8827717156529",
+                "0x587bc46f5f58e0511b93c31134652a689d761a9e7f234f0f130c52e4679f3a",
+                "0xbdb3180fdcfd6d6f172beb401af54dd71b6569e6061767234db2b777adf98b",
+            ),
+        ];
+        for (x, y, expected) in cases {
+            assert_eq!(poseidon_hash(hex(x), hex(y)), hex(expected));
+        }
+    }
+
+    #[test]
+    fn test_poseidon_hash_single() {
+        let cases = [
+            (
+                "0x9dad5d6f502ccbcb6d34ede04f0337df3b98936aaf782f4cc07d147e3a4fd6",
+                "0x11222854783f17f1c580ff64671bc3868de034c236f956216e8ed4ab7533455",
+            ),
+            (
+                "0x3164a8e2181ff7b83391b4a86bc8967f145c38f10f35fc74e9359a0c78f7b6",
+                "0x79ad7aa7b98d47705446fa01865942119026ac748d67a5840f06948bce2306b",
+            ),
+        ];
+        for (x, expected) in cases {
+            assert_eq!(poseidon_hash_single(hex(x)), hex(expected));
+        }
+    }
+
+    #[test]
+    fn test_poseidon_hash_many() {
+        let cases: [(&[&str], &str); 2] = [
+            (
+                &[
+                    "0x9bf52404586087391c5fbb42538692e7ca2149bac13c145ae4230a51a6fc47",
+                    "0x40304159ee9d2d611120fbd7c7fb8020cc8f7a599bfa108e0e085222b862c0",
+                    "0x46286e4f3c450761d960d6a151a9c0988f9e16f8a48d4c0a85817c009f806a",
+                ],
+                "0x1ec38b38dc88bac7b0ed6ff6326f975a06a59ac601b417745fd412a5d38e4f7",
+            ),
+            (
+                &[
+                    "0xbdace8883922662601b2fd197bb660b081fcf383ede60725bd080d4b5f2fd3",
+                    "0x1eb1daaf3fdad326b959dec70ced23649cdf8786537cee0c5758a1a4229097",
+                    "0x869ca04071b779d6f940cdf33e62d51521e19223ab148ef571856ff3a44ff1",
+                    "0x533e6df8d7c4b634b1f27035c8676a7439c635e1fea356484de7f0de677930",
+                ],
+                "0x2520b8f910174c3e650725baacad4efafaae7623c69a0b5513d75e500f36624",
+            ),
+        ];
+        for (inputs, expected) in cases {
+            let inputs: Vec<Felt> = inputs.iter().map(|x| hex(x)).collect();
+            assert_eq!(poseidon_hash_many(&inputs), hex(expected));
+        }
+    }
+
+    #[test]
+    fn test_round_keys() {
+        // The first and last rows of `poseidon3.txt`.
+        let first = [
+            "2950795762459345168613727575620414179244544320470208355568817838579231751791",
+            "1587446564224215276866294500450702039420286416111469274423465069420553242820",
+            "1645965921169490687904413452218868659025437693527479459426157555728339600137",
+        ];
+        let last = [
+            "2792703718581084537295613508201818489836796608902614779596544185252826291584",
+            "2294173715793292812015960640392421991604150133581218254866878921346561546149",
+            "2770011224727997178743274791849308200493823127651418989170761007078565678171",
+        ];
+        let keys = |row: usize| [0, 1, 2].map(|i| round_key(3 * row + i));
+        assert_eq!(keys(0), first.map(|key| Felt::from_str(key).unwrap()));
+        let last_row = ROUNDS_F + ROUNDS_P - 1;
+        assert_eq!(keys(last_row), last.map(|key| Felt::from_str(key).unwrap()));
+    }
+
+    fn arb_felt() -> impl Strategy<Value = Felt> {
+        any::<[u8; 32]>().prop_map(|bytes| Felt::from_le_bytes_mod_order(&bytes))
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn reversed_mds_matches_matrix(state in proptest::array::uniform3(arb_felt())) {
+            let mds = ReferenceParameters::starknet().mds;
+            let [c, b, a] = core::array::from_fn(|i| (0..3).map(|j| mds[i][j] * state[2 - j]).sum::<Felt>());
+            let [a0, b0, c0] = state;
+            prop_assert_eq!([a, b, c], [a0.double() + a0 + b0 + c0, a0 - b0 + c0, a0 + b0 - c0.double()]);
+        }
+    }
+}
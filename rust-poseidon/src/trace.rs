@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use crate::field::{decimal, Fr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    Ark,
+    Sbox,
+    Mix,
+    MixLast,
+}
+
+/// The state right after one step of round `round`.
+///
+/// Rounds are numbered 0 to 64: four full rounds, the 57 partial rounds, then the last
+/// four full rounds. The initial ARK is recorded as round 0.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub round: usize,
+    pub step: Step,
+    #[serde(with = "decimal::vec")]
+    pub state: Vec<Fr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trace {
+    #[serde(with = "decimal::vec")]
+    pub inputs: Vec<Fr>,
+    #[serde(with = "decimal")]
+    pub output: Fr,
+    pub steps: Vec<TraceStep>,
+}
+
+impl Trace {
+    pub fn new(inputs: &[Fr]) -> Self {
+        Trace {
+            inputs: inputs.to_vec(),
+            output: Fr::default(),
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, round: usize, step: Step, state: &[Fr]) {
+        if step == Step::MixLast {
+            self.output = state[0];
+        }
+        self.steps.push(TraceStep {
+            round,
+            step,
+            state: state.to_vec(),
+        });
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("traces always serialize")
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// The first step at which the two traces disagree, as `(ours, theirs)`.
+    ///
+    /// A step missing from the shorter trace counts as a disagreement.
+    pub fn first_divergence<'a>(
+        &'a self,
+        other: &'a Trace,
+    ) -> Option<(Option<&'a TraceStep>, Option<&'a TraceStep>)> {
+        (0..self.steps.len().max(other.steps.len()))
+            .map(|i| (self.steps.get(i), other.steps.get(i)))
+            .find(|(ours, theirs)| ours != theirs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functional::poseidon_hash_traced;
+
+    #[test]
+    fn test_trace_json_round_trip() {
+        let (_, trace) = poseidon_hash_traced(Fr::from(1), Fr::from(2));
+        let json = trace.to_json();
+
+        assert!(json.contains(r#""step": "sbox""#));
+        assert!(json.contains(
+            r#""output": "7853200120776062878684798364095072458815029376092732009249414926327459813530""#
+        ));
+        assert_eq!(Trace::from_json(&json).unwrap(), trace);
+    }
+
+    #[test]
+    fn test_trace_first_divergence() {
+        let (_, trace) = poseidon_hash_traced(Fr::from(1), Fr::from(2));
+        let mut other = trace.clone();
+        assert_eq!(trace.first_divergence(&other), None);
+
+        other.steps[100].state[2] += Fr::from(1);
+        other.steps[101].state[0] += Fr::from(1);
+        let (ours, theirs) = trace.first_divergence(&other).unwrap();
+        assert_eq!(ours, Some(&trace.steps[100]));
+        assert_eq!(theirs, Some(&other.steps[100]));
+
+        other.steps.truncate(10);
+        let (ours, theirs) = trace.first_divergence(&other).unwrap();
+        assert_eq!((ours, theirs), (Some(&trace.steps[10]), None));
+    }
+}
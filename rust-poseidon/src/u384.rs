@@ -0,0 +1,332 @@
+use std::{fmt, str::FromStr};
+
+use ark_ff::{BigInt, PrimeField};
+
+use crate::field::Fr;
+
+const LIMB_BITS: u32 = 96;
+const LIMB_MASK: u128 = (1 << LIMB_BITS) - 1;
+
+/// Mirror of Cairo's `core::circuit::u384`: four 96-bit limbs, least significant first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct U384 {
+    pub limb0: u128,
+    pub limb1: u128,
+    pub limb2: u128,
+    pub limb3: u128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum U384Error {
+    /// A limb does not fit in 96 bits.
+    LimbOverflow { limb: usize },
+    /// The value does not fit in 256 bits.
+    Overflow,
+    /// The value is not reduced modulo the BN254 scalar field order.
+    NonCanonical,
+    /// The text is not a `u384 { limb0: .., limb1: .., limb2: .., limb3: .. }` literal.
+    InvalidLiteral(String),
+}
+
+impl fmt::Display for U384Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            U384Error::LimbOverflow { limb } => write!(f, "limb{limb} does not fit in 96 bits"),
+            U384Error::Overflow => write!(f, "value does not fit in 256 bits"),
+            U384Error::NonCanonical => write!(f, "value is not a canonical field element"),
+            U384Error::InvalidLiteral(reason) => write!(f, "invalid u384 literal: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for U384Error {}
+
+impl U384 {
+    pub fn limbs(&self) -> [u128; 4] {
+        [self.limb0, self.limb1, self.limb2, self.limb3]
+    }
+
+    pub fn from_limbs(limbs: [u128; 4]) -> Result<Self, U384Error> {
+        if let Some(limb) = limbs.iter().position(|l| *l > LIMB_MASK) {
+            return Err(U384Error::LimbOverflow { limb });
+        }
+        let [limb0, limb1, limb2, limb3] = limbs;
+        Ok(U384 {
+            limb0,
+            limb1,
+            limb2,
+            limb3,
+        })
+    }
+
+    /// Formats the value with decimal limbs, as the tests in `poseidon/src/lib.cairo` spell it.
+    pub fn to_decimal_literal(&self) -> String {
+        format!(
+            "u384 {{ limb0: {}, limb1: {}, limb2: {}, limb3: {} }}",
+            self.limb0, self.limb1, self.limb2, self.limb3
+        )
+    }
+}
+
+impl From<BigInt<4>> for U384 {
+    fn from(value: BigInt<4>) -> Self {
+        let [w0, w1, w2, w3] = value.0;
+        let lo = w0 as u128 | (w1 as u128) << 64;
+        let hi = w2 as u128 | (w3 as u128) << 64;
+        U384 {
+            limb0: lo & LIMB_MASK,
+            limb1: (lo >> LIMB_BITS | hi << (128 - LIMB_BITS)) & LIMB_MASK,
+            limb2: hi >> (2 * LIMB_BITS - 128),
+            limb3: 0,
+        }
+    }
+}
+
+impl TryFrom<U384> for BigInt<4> {
+    type Error = U384Error;
+
+    fn try_from(value: U384) -> Result<Self, Self::Error> {
+        let value = U384::from_limbs(value.limbs())?;
+        if value.limb3 != 0 || value.limb2 >> (256 - 2 * LIMB_BITS) != 0 {
+            return Err(U384Error::Overflow);
+        }
+        let lo = value.limb0 | value.limb1 << LIMB_BITS;
+        let hi = value.limb1 >> (128 - LIMB_BITS) | value.limb2 << (2 * LIMB_BITS - 128);
+        Ok(BigInt([
+            lo as u64,
+            (lo >> 64) as u64,
+            hi as u64,
+            (hi >> 64) as u64,
+        ]))
+    }
+}
+
+impl From<Fr> for U384 {
+    fn from(value: Fr) -> Self {
+        value.into_bigint().into()
+    }
+}
+
+impl TryFrom<U384> for Fr {
+    type Error = U384Error;
+
+    fn try_from(value: U384) -> Result<Self, Self::Error> {
+        Fr::from_bigint(value.try_into()?).ok_or(U384Error::NonCanonical)
+    }
+}
+
+impl fmt::Display for U384 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "u384 {{ limb0: {:#x}, limb1: {:#x}, limb2: {:#x}, limb3: {:#x} }}",
+            self.limb0, self.limb1, self.limb2, self.limb3
+        )
+    }
+}
+
+/// Parses a Cairo `u384 { limb0: .., limb1: .., limb2: .., limb3: .. }` literal.
+///
+/// Limbs may be hex (`0x..`) or decimal, with `_` between digits, in any order, with an
+/// optional trailing comma.
+impl FromStr for U384 {
+    type Err = U384Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| U384Error::InvalidLiteral(reason.to_string());
+
+        let body = s
+            .trim()
+            .strip_prefix("u384")
+            .ok_or_else(|| invalid("missing `u384` prefix"))?
+            .trim_start()
+            .strip_prefix('{')
+            .and_then(|rest| rest.trim_end().strip_suffix('}'))
+            .ok_or_else(|| invalid("missing braces"))?;
+
+        let mut limbs = [None; 4];
+        for field in body.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            let (name, value) = field
+                .split_once(':')
+                .ok_or_else(|| invalid(&format!("expected `limbN: value`, got `{field}`")))?;
+            let index = match name.trim() {
+                "limb0" => 0,
+                "limb1" => 1,
+                "limb2" => 2,
+                "limb3" => 3,
+                other => return Err(invalid(&format!("unknown field `{other}`"))),
+            };
+            if limbs[index].is_some() {
+                return Err(invalid(&format!("duplicate field `limb{index}`")));
+            }
+            limbs[index] = Some(parse_limb(value.trim()).ok_or_else(|| {
+                invalid(&format!("limb{index} is not a number: `{}`", value.trim()))
+            })?);
+        }
+
+        let mut values = [0; 4];
+        for (index, limb) in limbs.into_iter().enumerate() {
+            values[index] = limb.ok_or_else(|| invalid(&format!("missing field `limb{index}`")))?;
+        }
+        U384::from_limbs(values)
+    }
+}
+
+/// A Cairo integer literal: decimal or `0x` digits, which `_` may separate but not lead.
+/// A sign is not part of the literal.
+fn parse_limb(value: &str) -> Option<u128> {
+    let (digits, radix) = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => (hex, 16),
+        None => (value, 10),
+    };
+    if !digits.starts_with(|c: char| c.is_digit(radix)) {
+        return None;
+    }
+    u128::from_str_radix(&digits.replace('_', ""), radix).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{AdditiveGroup, Field};
+
+    use super::*;
+
+    #[test]
+    fn test_u384_from_fr() {
+        let value = Fr::from_be_bytes_mod_order(
+            &prefix_hex::decode::<Vec<u8>>(
+                "0x0ee9a592ba9a9518d05986d656f40c2114c4993c11bb29938d21d47304cd8e6e",
+            )
+            .unwrap(),
+        );
+
+        let expected = U384 {
+            limb0: 0x11bb29938d21d47304cd8e6e,
+            limb1: 0xd05986d656f40c2114c4993c,
+            limb2: 0xee9a592ba9a9518,
+            limb3: 0x0,
+        };
+        assert_eq!(U384::from(value), expected);
+        assert_eq!(Fr::try_from(expected), Ok(value));
+    }
+
+    #[test]
+    fn test_u384_round_trip() {
+        for value in [Fr::ZERO, Fr::ONE, -Fr::ONE, Fr::from(u128::MAX)] {
+            assert_eq!(Fr::try_from(U384::from(value)), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_u384_rejects_out_of_range() {
+        let modulus = U384::from(Fr::MODULUS);
+        assert_eq!(Fr::try_from(modulus), Err(U384Error::NonCanonical));
+
+        let wide = U384 {
+            limb2: 1 << 64,
+            ..Default::default()
+        };
+        assert_eq!(BigInt::<4>::try_from(wide), Err(U384Error::Overflow));
+
+        let overflowing = U384 {
+            limb1: 1 << 96,
+            ..Default::default()
+        };
+        assert_eq!(
+            BigInt::<4>::try_from(overflowing),
+            Err(U384Error::LimbOverflow { limb: 1 })
+        );
+    }
+
+    #[test]
+    fn test_u384_parse() {
+        let parsed: U384 = "u384 {
+            limb0: 0x11bb29938d21d47304cd8e6e,
+            limb1: 0xd05986d656f40c2114c4993c,
+            limb2: 0xee9a592ba9a9518,
+            limb3: 0x0,
+        }"
+        .parse()
+        .unwrap();
+        assert_eq!(parsed.limb2, 0xee9a592ba9a9518);
+
+        let decimal: U384 = "u384 { limb0: 35961034779122590217458358426, limb1: 19176342235218042678530152434, limb2: 1251086958891274305, limb3: 0 }"
+            .parse()
+            .unwrap();
+        assert_eq!(decimal.to_string().parse::<U384>(), Ok(decimal));
+        assert_eq!(decimal.to_decimal_literal().parse::<U384>(), Ok(decimal));
+
+        assert!("u384 { limb0: 1, limb1: 2, limb2: 3 }"
+            .parse::<U384>()
+            .is_err());
+        assert!("u384 { limb0: 1, limb0: 2, limb2: 3, limb3: 4 }"
+            .parse::<U384>()
+            .is_err());
+        assert!("u256 { low: 1, high: 2 }".parse::<U384>().is_err());
+
+        // Literals garaga's Cairo rejects: a sign, and digits that are missing or start
+        // with a separator.
+        for limb in [
+            "+1", "-1", "+0x1", "0x+1", "_", "__", "_1", "0x", "0x_", "0x_1", "",
+        ] {
+            let literal = format!("u384 {{ limb0: {limb}, limb1: 0, limb2: 0, limb3: 0 }}");
+            assert!(literal.parse::<U384>().is_err(), "{literal}");
+        }
+        let separated: U384 = "u384 { limb0: 1_000, limb1: 0xff_ff, limb2: 0, limb3: 0 }"
+            .parse()
+            .unwrap();
+        assert_eq!((separated.limb0, separated.limb1), (1000, 0xffff));
+    }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use super::*;
+        use crate::field::arb_fr;
+
+        proptest! {
+            #[test]
+            fn fr_round_trip(value in arb_fr()) {
+                let limbs = U384::from(value);
+                prop_assert!(limbs.limbs().iter().all(|limb| *limb <= LIMB_MASK));
+                prop_assert_eq!(Fr::try_from(limbs), Ok(value));
+            }
+
+            #[test]
+            fn literal_round_trip(limbs in proptest::array::uniform4(0..=LIMB_MASK)) {
+                let value = U384::from_limbs(limbs).unwrap();
+                prop_assert_eq!(value.to_string().parse::<U384>(), Ok(value));
+                prop_assert_eq!(value.to_decimal_literal().parse::<U384>(), Ok(value));
+            }
+
+            #[test]
+            fn parse_never_panics(text in "u384 \\{[a-z0-9:, x_]{0,80}\\}|\\PC{0,40}") {
+                let _ = text.parse::<U384>();
+            }
+
+            #[test]
+            fn rejects_signed_and_empty_limbs(
+                limb in 0..=LIMB_MASK,
+                sign in "[+-]",
+                prefix in "(0x)?",
+                separators in "_{0,4}",
+            ) {
+                let literal = |limb0: &str| {
+                    format!("u384 {{ limb0: {limb0}, limb1: 0, limb2: 0, limb3: 0 }}")
+                };
+                let rejected = [
+                    format!("{sign}{limb}"),
+                    format!("{sign}{limb:#x}"),
+                    format!("{prefix}{separators}"),
+                    format!("{prefix}_{limb}"),
+                ];
+                for limb0 in rejected {
+                    prop_assert!(literal(&limb0).parse::<U384>().is_err(), "{}", limb0);
+                }
+            }
+        }
+    }
+}
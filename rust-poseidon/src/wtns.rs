@@ -0,0 +1,213 @@
+use std::fmt;
+
+use ark_ff::{BigInt, PrimeField};
+
+use crate::field::Fr;
+
+/// Index of the first output signal in a circom witness; index 0 is the constant one.
+pub const FIRST_OUTPUT: usize = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WtnsError {
+    Truncated,
+    Magic,
+    Version(u32),
+    MissingSection(u32),
+    FieldSize(u32),
+    Prime,
+    WitnessCount { declared: u32, found: usize },
+    NonCanonical { index: usize },
+}
+
+impl fmt::Display for WtnsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WtnsError::Truncated => write!(f, "file is truncated"),
+            WtnsError::Magic => write!(f, "missing `wtns` magic"),
+            WtnsError::Version(version) => write!(f, "unsupported version {version}"),
+            WtnsError::MissingSection(section) => write!(f, "missing section {section}"),
+            WtnsError::FieldSize(n8) => write!(f, "unsupported field element size {n8}"),
+            WtnsError::Prime => write!(f, "witness is not over the BN254 scalar field"),
+            WtnsError::WitnessCount { declared, found } => write!(
+                f,
+                "header declares {declared} witness values but the file holds {found}"
+            ),
+            WtnsError::NonCanonical { index } => {
+                write!(f, "witness value {index} is not a canonical field element")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WtnsError {}
+
+/// Parses the binary witness file written by circom's `generate_witness.js`.
+pub fn parse_wtns(bytes: &[u8]) -> Result<Vec<Fr>, WtnsError> {
+    let mut reader = Reader(bytes);
+    if reader.take(4)? != b"wtns" {
+        return Err(WtnsError::Magic);
+    }
+    let version = reader.u32()?;
+    if version != 1 && version != 2 {
+        return Err(WtnsError::Version(version));
+    }
+
+    let mut sections = [None, None];
+    for _ in 0..reader.u32()? {
+        let kind = reader.u32()?;
+        let size = usize::try_from(reader.u64()?).map_err(|_| WtnsError::Truncated)?;
+        let data = reader.take(size)?;
+        if let Some(slot) = kind
+            .checked_sub(1)
+            .and_then(|i| sections.get_mut(i as usize))
+        {
+            *slot = Some(data);
+        }
+    }
+    let [header, values] = sections;
+    let mut header = Reader(header.ok_or(WtnsError::MissingSection(1))?);
+    let values = values.ok_or(WtnsError::MissingSection(2))?;
+
+    let n8 = header.u32()?;
+    if n8 != 32 {
+        return Err(WtnsError::FieldSize(n8));
+    }
+    if from_le_bytes(header.take(32)?) != Fr::MODULUS {
+        return Err(WtnsError::Prime);
+    }
+    let declared = header.u32()?;
+    if values.len() % 32 != 0 || values.len() / 32 != declared as usize {
+        return Err(WtnsError::WitnessCount {
+            declared,
+            found: values.len() / 32,
+        });
+    }
+
+    values
+        .chunks(32)
+        .enumerate()
+        .map(|(index, chunk)| {
+            Fr::from_bigint(from_le_bytes(chunk)).ok_or(WtnsError::NonCanonical { index })
+        })
+        .collect()
+}
+
+/// Serializes a witness the way `generate_witness.js` does (version 2).
+pub fn write_wtns(witness: &[Fr]) -> Vec<u8> {
+    let mut bytes = b"wtns".to_vec();
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend(2u32.to_le_bytes());
+
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(40u64.to_le_bytes());
+    bytes.extend(32u32.to_le_bytes());
+    bytes.extend(to_le_bytes(Fr::MODULUS));
+    bytes.extend((witness.len() as u32).to_le_bytes());
+
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend((witness.len() as u64 * 32).to_le_bytes());
+    for value in witness {
+        bytes.extend(to_le_bytes(value.into_bigint()));
+    }
+    bytes
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], WtnsError> {
+        if self.0.len() < n {
+            return Err(WtnsError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, WtnsError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, WtnsError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+fn from_le_bytes(bytes: &[u8]) -> BigInt<4> {
+    let mut words = [0; 4];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks(8)) {
+        *word = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    BigInt(words)
+}
+
+fn to_le_bytes(value: BigInt<4>) -> Vec<u8> {
+    value.0.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functional::poseidon_hash;
+
+    #[test]
+    fn test_wtns_round_trip() {
+        let output = poseidon_hash(Fr::from(1), Fr::from(2));
+        let witness = vec![Fr::from(1), output, Fr::from(1), Fr::from(2)];
+
+        let parsed = parse_wtns(&write_wtns(&witness)).unwrap();
+        assert_eq!(parsed, witness);
+        assert_eq!(parsed[FIRST_OUTPUT], output);
+    }
+
+    #[test]
+    fn test_wtns_rejects_malformed() {
+        let bytes = write_wtns(&[Fr::from(1), Fr::from(2)]);
+
+        assert_eq!(
+            parse_wtns(&bytes[..bytes.len() - 1]),
+            Err(WtnsError::Truncated)
+        );
+        assert_eq!(parse_wtns(b"r1cs\x01\0\0\0"), Err(WtnsError::Magic));
+
+        let mut other_prime = bytes.clone();
+        other_prime[32] ^= 1;
+        assert_eq!(parse_wtns(&other_prime), Err(WtnsError::Prime));
+
+        let mut non_canonical = bytes.clone();
+        let last = non_canonical.len() - 32;
+        non_canonical[last..].copy_from_slice(&to_le_bytes(Fr::MODULUS));
+        assert_eq!(
+            parse_wtns(&non_canonical),
+            Err(WtnsError::NonCanonical { index: 1 })
+        );
+    }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use super::*;
+        use crate::field::arb_fr;
+
+        proptest! {
+            #[test]
+            fn write_parse_round_trip(witness in proptest::collection::vec(arb_fr(), 0..16)) {
+                prop_assert_eq!(parse_wtns(&write_wtns(&witness)), Ok(witness));
+            }
+
+            #[test]
+            fn parse_never_panics(
+                witness in proptest::collection::vec(arb_fr(), 0..4),
+                index in any::<prop::sample::Index>(),
+                byte in any::<u8>(),
+                truncate in any::<prop::sample::Index>(),
+            ) {
+                let mut bytes = write_wtns(&witness);
+                let i = index.index(bytes.len());
+                bytes[i] = byte;
+                let len = truncate.index(bytes.len() + 1);
+                let _ = parse_wtns(&bytes[..len]);
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! circomlibjs-compatible Poseidon for JavaScript.
+//!
+//! `poseidon(inputs, initState, nOut)` follows circomlibjs for 1 to 16 inputs. Inputs are
+//! converted like JavaScript's `BigInt()`, from bigints, integer numbers, booleans or
+//! strings (decimal, optionally signed, or `0x`, `0o` and `0b` digits), and reduced modulo
+//! the BN254 scalar field order like `F.e`. The result is a bigint, or an array of the
+//! first `nOut` state elements when `nOut` is above 1.
+
+use std::fmt;
+
+use rust_poseidon::{circomlib, field::Fr};
+use wasm_bindgen::prelude::*;
+
+pub use rust_poseidon::circomlib::MAX_INPUTS;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// An input is not an integer, or not a bigint, number, boolean or string.
+    InvalidInput { index: Option<usize>, value: String },
+    /// circomlibjs hashes 1 to `MAX_INPUTS` inputs.
+    Arity(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidInput {
+                index: Some(index),
+                value,
+            } => write!(f, "input {index} is not an integer: {value}"),
+            Error::InvalidInput { index: None, value } => {
+                write!(f, "initState is not an integer: {value}")
+            }
+            Error::Arity(n) => write!(f, "{n} inputs given, expected 1 to {MAX_INPUTS}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Parses an integer the way JavaScript's `BigInt(text)` does, then reduces it like
+/// circomlibjs' `F.e`. As in JavaScript, surrounding whitespace is ignored and a blank
+/// string is zero; only decimal digits may carry a sign.
+pub fn parse_element(text: &str) -> Option<Fr> {
+    let text = text.trim_matches(is_js_whitespace);
+    if text.is_empty() {
+        return Some(Fr::from(0));
+    }
+    let prefixed = [
+        ("0x", 16),
+        ("0X", 16),
+        ("0o", 8),
+        ("0O", 8),
+        ("0b", 2),
+        ("0B", 2),
+    ]
+    .into_iter()
+    .find_map(|(prefix, radix)| text.strip_prefix(prefix).map(|digits| (digits, radix)));
+    let (negative, digits, radix) = match prefixed {
+        Some((digits, radix)) => (false, digits, radix),
+        None => match text.strip_prefix('-') {
+            Some(decimal) => (true, decimal, 10),
+            None => (false, text.strip_prefix('+').unwrap_or(text), 10),
+        },
+    };
+    if digits.is_empty() {
+        return None;
+    }
+
+    let radix_fr = Fr::from(radix);
+    let value = digits.chars().try_fold(Fr::from(0), |acc, c| {
+        c.to_digit(radix).map(|d| acc * radix_fr + Fr::from(d))
+    })?;
+    Some(if negative { -value } else { value })
+}
+
+/// JavaScript's `WhiteSpace` and `LineTerminator` characters, which `BigInt` trims.
+fn is_js_whitespace(c: char) -> bool {
+    c == '\u{feff}' || (c.is_whitespace() && c != '\u{85}')
+}
+
+/// circomlibjs' `poseidon(inputs, initState, nOut)` on already parsed inputs: the first
+/// `n_out` elements of the permuted `[init_state, inputs...]`, or the whole state if it
+/// is shorter.
+pub fn hash(inputs: &[Fr], init_state: Fr, n_out: usize) -> Result<Vec<Fr>, Error> {
+    let state: Vec<Fr> = std::iter::once(init_state)
+        .chain(inputs.iter().copied())
+        .collect();
+    let mut state = circomlib::permute(&state).ok_or(Error::Arity(inputs.len()))?;
+    state.truncate(n_out);
+    Ok(state)
+}
+
+fn element(value: &JsValue, index: Option<usize>) -> Result<Fr, Error> {
+    let text = if value.is_bigint() {
+        js_sys::BigInt::unchecked_from_js_ref(value)
+            .to_string(10)
+            .ok()
+            .map(String::from)
+    } else if let Some(number) = value.as_f64() {
+        (number.is_finite() && number.fract() == 0.0).then(|| format!("{number:.0}"))
+    } else if let Some(boolean) = value.as_bool() {
+        Some(u8::from(boolean).to_string())
+    } else {
+        value.as_string()
+    };
+    text.as_deref()
+        .and_then(parse_element)
+        .ok_or_else(|| Error::InvalidInput {
+            index,
+            value: format!("{value:?}"),
+        })
+}
+
+fn bigint(value: &Fr) -> js_sys::BigInt {
+    js_sys::BigInt::new(&JsValue::from_str(&value.to_string()))
+        .expect("a field element is a valid bigint literal")
+}
+
+/// Hashes `inputs` like circomlibjs' `poseidon`: a bigint, or an array of `nOut` bigints
+/// when `nOut` is above 1. A missing or falsy `initState` is zero, and a missing or zero
+/// `nOut` is 1, as with circomlibjs' `initState ? ... : F.zero` and `nOut || 1`.
+#[wasm_bindgen(unchecked_return_type = "bigint | bigint[]")]
+pub fn poseidon(
+    inputs: Vec<JsValue>,
+    #[wasm_bindgen(js_name = initState)] init_state: Option<JsValue>,
+    #[wasm_bindgen(js_name = nOut)] n_out: Option<u32>,
+) -> Result<JsValue, JsError> {
+    let inputs = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, value)| element(value, Some(index)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let init_state = match init_state {
+        Some(value) if value.is_truthy() => element(&value, None)?,
+        _ => Fr::from(0),
+    };
+    let n_out = match n_out {
+        None | Some(0) => 1,
+        Some(n) => n as usize,
+    };
+    let outputs = hash(&inputs, init_state, n_out)?;
+    Ok(if n_out == 1 {
+        bigint(&outputs[0]).into()
+    } else {
+        outputs.iter().map(bigint).collect::<js_sys::Array>().into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_decimal(inputs: &[Fr]) -> Result<String, Error> {
+        hash(inputs, Fr::from(0), 1).map(|result| result[0].to_string())
+    }
+
+    #[test]
+    fn test_parse_element() {
+        assert_eq!(parse_element("2"), Some(Fr::from(2)));
+        assert_eq!(parse_element("+2"), Some(Fr::from(2)));
+        assert_eq!(parse_element(" 0x1F "), Some(Fr::from(31)));
+        assert_eq!(parse_element("-1"), Some(-Fr::from(1)));
+        assert_eq!(
+            parse_element(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495619"
+            ),
+            Some(Fr::from(2))
+        );
+        for invalid in [
+            "0x", "-", "+", "1.5", "0x-1", "-0x1", "12a", "1e3", "1n", "1_000", "0b", "0b2",
+            "-0b1", "0o", "0o8", "+0o7", "\u{85}1",
+        ] {
+            assert_eq!(parse_element(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_element_like_bigint() {
+        // `BigInt("")`, `BigInt(" \n")`, `BigInt("0b101")`, `BigInt("0O17")` and so on.
+        for (text, value) in [
+            ("", 0),
+            (" \t\n\u{a0}\u{feff}", 0),
+            ("0b101", 5),
+            ("0B1", 1),
+            ("0o17", 15),
+            ("0O7", 7),
+            ("\u{2028}0b11\u{3000}", 3),
+            ("-0", 0),
+        ] {
+            assert_eq!(parse_element(text), Some(Fr::from(value)), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn test_hash_circomlibjs_vectors() {
+        // circomlibjs' poseidon test vectors.
+        assert_eq!(
+            hash_decimal(&[Fr::from(1), Fr::from(2)]).unwrap(),
+            "7853200120776062878684798364095072458815029376092732009249414926327459813530"
+        );
+        assert_eq!(
+            hash_decimal(&[Fr::from(0), Fr::from(0)]).unwrap(),
+            "14744269619966411208579211824598458697587494354926760081771325075741142829156"
+        );
+        assert_eq!(
+            hash_decimal(&[1, 2, 3, 4].map(Fr::from)).unwrap(),
+            "18821383157269793795438455681495246036402687001665670618754263018637548127333"
+        );
+        let sixteen: Vec<Fr> = (1..=16u64).map(Fr::from).collect();
+        assert_eq!(
+            hash_decimal(&sixteen).unwrap(),
+            "9989051620750914585850546081941653841776809718687451684622678807385399211877"
+        );
+    }
+
+    #[test]
+    fn test_hash_outputs() {
+        let inputs = [Fr::from(1), Fr::from(2)];
+        let state = hash(&inputs, Fr::from(0), 3).unwrap();
+        assert_eq!(state.len(), 3);
+        assert_eq!(hash(&inputs, Fr::from(0), 1).unwrap(), state[..1]);
+        assert_eq!(hash(&inputs, Fr::from(0), 2).unwrap(), state[..2]);
+        assert_eq!(hash(&inputs, Fr::from(0), 10).unwrap(), state);
+    }
+
+    #[test]
+    fn test_hash_arity() {
+        assert_eq!(hash(&[], Fr::from(0), 1), Err(Error::Arity(0)));
+        assert!(hash(&[Fr::from(1)], Fr::from(0), 1).is_ok());
+        assert!(hash(&[Fr::from(1); MAX_INPUTS], Fr::from(0), 1).is_ok());
+        assert_eq!(
+            hash(&[Fr::from(1); MAX_INPUTS + 1], Fr::from(0), 1),
+            Err(Error::Arity(MAX_INPUTS + 1))
+        );
+    }
+}
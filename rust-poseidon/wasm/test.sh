@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+# Runs the wasm tests in Node: the `wasm_bindgen_test`s of tests/web.rs, then the
+# known-answer (and, when circomlibjs is installed, random) comparison of tests/circomlibjs.mjs.
+#
+# Needs the wasm32-unknown-unknown target and the `wasm-bindgen` CLI at the version the
+# workspace resolves, which provides `wasm-bindgen-test-runner`:
+#
+#   rustup target add wasm32-unknown-unknown
+#   cargo install wasm-bindgen-cli --version "$(./wasm/test.sh --wasm-bindgen-version)"
+#
+# Extra arguments are passed to `cargo test`, e.g. --offline.
+set -euo pipefail
+cd "$(dirname "$0")/.."
+
+version=$(cargo pkgid -p wasm-bindgen | sed 's/.*@//')
+if [ "${1:-}" = --wasm-bindgen-version ]; then
+    echo "$version"
+    exit 0
+fi
+installed=$(wasm-bindgen --version | cut -d' ' -f2)
+if [ "$installed" != "$version" ]; then
+    echo "wasm-bindgen $installed is installed, but the workspace uses $version" >&2
+    exit 1
+fi
+
+target_dir="${CARGO_TARGET_DIR:-target}"
+CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner \
+    cargo test -p rust-poseidon-wasm --target wasm32-unknown-unknown "$@"
+
+cargo build -p rust-poseidon-wasm --target wasm32-unknown-unknown --release "$@"
+wasm-bindgen --target nodejs --out-dir wasm/pkg \
+    "$target_dir/wasm32-unknown-unknown/release/rust_poseidon_wasm.wasm"
+node wasm/tests/circomlibjs.mjs
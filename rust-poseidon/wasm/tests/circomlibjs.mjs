@@ -0,0 +1,54 @@
+// Compares the wasm build against the known-answer vectors and, when the `circomlibjs`
+// package can be resolved, against circomlibjs itself on random inputs.
+//
+//   wasm-pack build --target nodejs wasm
+//   node wasm/tests/circomlibjs.mjs
+//
+// `wasm/test.sh` builds the package with `wasm-bindgen` and runs this after the Rust tests.
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { createRequire } from "node:module";
+
+const require = createRequire(import.meta.url);
+const { poseidon } = require("../pkg/rust_poseidon_wasm.js");
+
+const { vectors } = JSON.parse(
+  readFileSync(new URL("../../vectors/poseidon.json", import.meta.url), "utf8"),
+);
+
+let checked = 0;
+for (const vector of vectors) {
+  const result = poseidon(vector.inputs.map(BigInt), BigInt(vector.initial_state));
+  assert.equal(result, BigInt(vector.expected), JSON.stringify(vector.inputs));
+  checked++;
+}
+
+let circomlibjs;
+try {
+  circomlibjs = require("circomlibjs");
+} catch {
+  console.log("circomlibjs not installed, skipping the random comparison");
+}
+if (circomlibjs) {
+  const reference = await circomlibjs.buildPoseidon();
+  const p = reference.F.p;
+  const random = () =>
+    BigInt("0x" + [...crypto.getRandomValues(new Uint8Array(32))]
+      .map((b) => b.toString(16).padStart(2, "0")).join("")) % p;
+  for (let i = 0; i < 256; i++) {
+    const arity = 1 + (i % 16);
+    const inputs = Array.from({ length: arity }, random);
+    const initState = i % 3 === 0 ? random() : undefined;
+    const expected = reference.F.toObject(reference(inputs, initState));
+    assert.equal(poseidon(inputs, initState), expected, inputs.join(","));
+    assert.equal(poseidon(inputs.map(String), initState), expected);
+    const nOut = 1 + (i % (arity + 1));
+    const outputs = reference(inputs, initState, nOut);
+    const expectedOut = nOut === 1
+      ? reference.F.toObject(outputs)
+      : outputs.map((x) => reference.F.toObject(x));
+    assert.deepEqual(poseidon(inputs, initState, nOut), expectedOut);
+    checked++;
+  }
+}
+console.log(`${checked} checks passed`);
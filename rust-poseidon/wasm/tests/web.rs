@@ -0,0 +1,97 @@
+//! Runs in a wasm runtime: `wasm/test.sh`, or `wasm-pack test --node wasm`.
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Array, BigInt};
+use rust_poseidon_wasm::poseidon;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const ONE_TWO: &str =
+    "7853200120776062878684798364095072458815029376092732009249414926327459813530";
+
+fn bigint(text: &str) -> JsValue {
+    BigInt::new(&JsValue::from_str(text)).unwrap().into()
+}
+
+fn decimal(value: JsValue) -> String {
+    String::from(value.unchecked_into::<BigInt>().to_string(10).unwrap())
+}
+
+#[wasm_bindgen_test]
+fn accepts_circomlibjs_input_types() {
+    for inputs in [
+        vec![bigint("1"), bigint("2")],
+        vec![JsValue::from(1), JsValue::from(2)],
+        vec![JsValue::from_str("1"), JsValue::from_str("0x2")],
+        vec![JsValue::from_str("0b1"), JsValue::from_str(" 0o2 ")],
+        vec![JsValue::TRUE, JsValue::from(2.0)],
+    ] {
+        assert_eq!(decimal(poseidon(inputs, None, None).unwrap()), ONE_TWO);
+    }
+}
+
+#[wasm_bindgen_test]
+fn reduces_like_field_e() {
+    let p_plus_one =
+        "21888242871839275222246405745257275088548364400416034343698204186575808495618";
+    let result = poseidon(vec![bigint(p_plus_one), bigint("2")], None, None).unwrap();
+    assert_eq!(decimal(result), ONE_TWO);
+}
+
+#[wasm_bindgen_test]
+fn init_state() {
+    let zero = poseidon(vec![bigint("1"), bigint("2")], Some(bigint("0")), Some(1)).unwrap();
+    assert_eq!(decimal(zero), ONE_TWO);
+    let other = poseidon(vec![bigint("1"), bigint("2")], Some(bigint("7")), None).unwrap();
+    assert_ne!(decimal(other), ONE_TWO);
+}
+
+#[wasm_bindgen_test]
+fn init_state_like_circomlibjs() {
+    // A falsy `initState` is zero, and `BigInt("")` is too.
+    for init_state in [
+        JsValue::NULL,
+        JsValue::FALSE,
+        JsValue::from(0),
+        JsValue::from_str(""),
+    ] {
+        let result = poseidon(vec![bigint("1"), bigint("2")], Some(init_state), None).unwrap();
+        assert_eq!(decimal(result), ONE_TWO);
+    }
+}
+
+#[wasm_bindgen_test]
+fn hashes_one_to_sixteen_inputs() {
+    let four = ["1", "2", "3", "4"].map(bigint).to_vec();
+    assert_eq!(
+        decimal(poseidon(four, None, None).unwrap()),
+        "18821383157269793795438455681495246036402687001665670618754263018637548127333"
+    );
+    let sixteen = (1..=16).map(JsValue::from).collect();
+    assert_eq!(
+        decimal(poseidon(sixteen, None, None).unwrap()),
+        "9989051620750914585850546081941653841776809718687451684622678807385399211877"
+    );
+    assert!(poseidon(vec![bigint("1")], None, None).is_ok());
+}
+
+#[wasm_bindgen_test]
+fn returns_n_out_elements() {
+    let outputs = Array::from(&poseidon(vec![bigint("1"), bigint("2")], None, Some(2)).unwrap());
+    assert_eq!(outputs.length(), 2);
+    assert_eq!(decimal(outputs.get(0)), ONE_TWO);
+    let all = Array::from(&poseidon(vec![bigint("1"), bigint("2")], None, Some(5)).unwrap());
+    assert_eq!(all.length(), 3);
+    assert_eq!(decimal(all.get(1)), decimal(outputs.get(1)));
+    let one = poseidon(vec![bigint("1"), bigint("2")], None, Some(0)).unwrap();
+    assert_eq!(decimal(one), ONE_TWO);
+}
+
+#[wasm_bindgen_test]
+fn rejects_unsupported_calls() {
+    assert!(poseidon(vec![], None, None).is_err());
+    assert!(poseidon((0..17).map(JsValue::from).collect(), None, None).is_err());
+    assert!(poseidon(vec![JsValue::from(1.5), bigint("2")], None, None).is_err());
+    assert!(poseidon(vec![JsValue::NULL, bigint("2")], None, None).is_err());
+    assert!(poseidon(vec![JsValue::from_str("-0x1"), bigint("2")], None, None).is_err());
+}